@@ -35,6 +35,10 @@ fn hello_action(c: &Context) {
             FlagError::Undefined => println!("undefined age flag"),
             FlagError::ArgumentError => println!("age flag argument error"),
             FlagError::NotFound => println!("not found age flag"),
+            FlagError::UnexpectedValue(values) => {
+                println!("age flag expects one of {}", values.join(", "))
+            }
+            _ => println!("age flag error"),
         },
     }
 
@@ -46,6 +50,10 @@ fn hello_action(c: &Context) {
             FlagError::Undefined => println!("undefined neko flag"),
             FlagError::ArgumentError => println!("neko flag argument error"),
             FlagError::NotFound => println!("not found neko flag"),
+            FlagError::UnexpectedValue(values) => {
+                println!("neko flag expects one of {}", values.join(", "))
+            }
+            _ => println!("neko flag error"),
         },
     }
 }
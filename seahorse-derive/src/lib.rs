@@ -0,0 +1,374 @@
+//! Derive macro for [seahorse](https://docs.rs/seahorse/), enabled by its `derive` feature
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, Attribute, Data, DeriveInput, Error, Expr, Fields, GenericArgument, Ident,
+    Lit, LitStr, Meta, PathArguments, Result, Type,
+};
+
+/// Generate `command()` returning the `Command` with a flag for every field,
+/// and a `FromContext` impl filling the fields from the flags
+///
+/// Doc comments become the descriptions of the command and the flags.
+///
+/// - `#[command(name = "..", alias = "..", description = "..")]` on the struct
+/// - `#[flag(name = "..", alias = "..", description = "..", default_value = "..", env = "..", global, hidden)]` on the fields
+/// - `#[args]` on a `Vec<String>` field to get the positional arguments
+///
+/// Fields of `bool`, `String`, `isize`, `usize` and `f64` are flags of the matching type,
+/// required unless they have a default value. `Option<T>` makes the flag optional
+/// and `Vec<T>` accepts the flag multiple times, except for `bool` which is already optional.
+#[proc_macro_derive(Seahorse, attributes(command, flag, args))]
+pub fn derive_seahorse(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// How the field value is read from the context
+enum Shape {
+    /// `bool`, false when the flag is not specified
+    Bool,
+    /// `T`
+    Required,
+    /// `Option<T>`
+    Optional,
+    /// `Vec<T>`
+    Multiple,
+    /// `Vec<String>` with `#[args]`
+    Args,
+}
+
+struct FieldSpec {
+    ident: Ident,
+    shape: Shape,
+    /// Name of `FlagType` variant and getter of `Context` without `_flag`
+    kind: Option<(Ident, &'static str)>,
+    name: String,
+    description: Option<String>,
+    aliases: Vec<String>,
+    default_value: Option<String>,
+    env: Option<String>,
+    global: bool,
+    hidden: bool,
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "Seahorse can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "Seahorse can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut name = kebab_case(&input.ident.to_string());
+    let mut description = doc_comment(&input.attrs);
+    let mut aliases = Vec::new();
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("command")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                name = meta.value()?.parse::<LitStr>()?.value();
+            } else if meta.path.is_ident("alias") {
+                aliases.push(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("description") {
+                description = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else {
+                return Err(meta.error("expected `name`, `alias` or `description`"));
+            }
+            Ok(())
+        })?;
+    }
+
+    let specs = fields
+        .iter()
+        .map(|field| field_spec(field.ident.clone().unwrap(), &field.ty, &field.attrs))
+        .collect::<Result<Vec<_>>>()?;
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let description = description.map(|d| quote!(.description(#d)));
+    let flags = specs.iter().filter_map(flag_tokens);
+    let values = specs.iter().map(value_tokens);
+    let idents = specs.iter().map(|s| &s.ident);
+    let locals = specs.iter().map(|s| local_ident(&s.ident));
+
+    Ok(quote! {
+        impl #impl_generics #ident #ty_generics #where_clause {
+            /// Get `Command` with the flags of the fields
+            pub fn command() -> ::seahorse::Command {
+                ::seahorse::Command::new(#name)
+                    #description
+                    #(.alias(#aliases))*
+                    #(.flag(#flags))*
+            }
+        }
+
+        impl #impl_generics ::seahorse::FromContext for #ident #ty_generics #where_clause {
+            fn from_context(
+                __seahorse_c: &::seahorse::Context,
+            ) -> ::std::result::Result<Self, ::seahorse::error::ContextError> {
+                let mut __seahorse_errors = ::seahorse::error::ContextError::default();
+                #(#values)*
+                __seahorse_errors.into_result()?;
+                ::std::result::Result::Ok(Self {
+                    #(#idents: #locals.unwrap(),)*
+                })
+            }
+        }
+    })
+}
+
+fn field_spec(ident: Ident, ty: &Type, attrs: &[Attribute]) -> Result<FieldSpec> {
+    let mut spec = FieldSpec {
+        name: ident.to_string().trim_start_matches("r#").replace('_', "-"),
+        ident,
+        shape: Shape::Required,
+        kind: None,
+        description: doc_comment(attrs),
+        aliases: Vec::new(),
+        default_value: None,
+        env: None,
+        global: false,
+        hidden: false,
+    };
+
+    if attrs.iter().any(|a| a.path().is_ident("args")) {
+        if !matches!(type_argument(ty, "Vec"), Some(t) if is_type(t, "String")) {
+            return Err(Error::new_spanned(
+                ty,
+                "#[args] field must be `Vec<String>`",
+            ));
+        }
+        spec.shape = Shape::Args;
+        return Ok(spec);
+    }
+
+    for attr in attrs.iter().filter(|a| a.path().is_ident("flag")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("global") {
+                spec.global = true;
+                return Ok(());
+            }
+            if meta.path.is_ident("hidden") {
+                spec.hidden = true;
+                return Ok(());
+            }
+
+            let value = meta.value()?.parse::<LitStr>()?.value();
+            if meta.path.is_ident("name") {
+                spec.name = value;
+            } else if meta.path.is_ident("alias") {
+                spec.aliases.push(value);
+            } else if meta.path.is_ident("description") {
+                spec.description = Some(value);
+            } else if meta.path.is_ident("default_value") {
+                spec.default_value = Some(value);
+            } else if meta.path.is_ident("env") {
+                spec.env = Some(value);
+            } else {
+                return Err(meta.error(
+                    "expected `name`, `alias`, `description`, `default_value`, `env`, `global` or `hidden`",
+                ));
+            }
+            Ok(())
+        })?;
+    }
+
+    let inner = if is_type(ty, "bool") {
+        spec.shape = Shape::Bool;
+        spec.kind = Some((Ident::new("Bool", proc_macro2::Span::call_site()), "bool"));
+        return Ok(spec);
+    } else if let Some(inner) = type_argument(ty, "Option") {
+        spec.shape = Shape::Optional;
+        inner
+    } else if let Some(inner) = type_argument(ty, "Vec") {
+        spec.shape = Shape::Multiple;
+        inner
+    } else {
+        ty
+    };
+
+    if is_type(inner, "bool") {
+        return Err(Error::new_spanned(
+            ty,
+            "`bool` cannot be wrapped in `Option` or `Vec`, use `bool` which is false when the flag is not specified",
+        ));
+    }
+
+    let (variant, getter) = if is_type(inner, "String") {
+        ("String", "string")
+    } else if is_type(inner, "isize") {
+        ("Int", "int")
+    } else if is_type(inner, "usize") {
+        ("Uint", "uint")
+    } else if is_type(inner, "f64") {
+        ("Float", "float")
+    } else {
+        return Err(Error::new_spanned(
+            ty,
+            "expected `bool`, or `String`, `isize`, `usize` or `f64` \
+             optionally wrapped in `Option` or `Vec`",
+        ));
+    };
+    spec.kind = Some((Ident::new(variant, proc_macro2::Span::call_site()), getter));
+    Ok(spec)
+}
+
+/// `Flag` definition of the field
+fn flag_tokens(spec: &FieldSpec) -> Option<TokenStream2> {
+    let (variant, _) = spec.kind.as_ref()?;
+    let name = &spec.name;
+    let description = spec.description.as_ref().map(|d| quote!(.description(#d)));
+    let aliases = &spec.aliases;
+    let default_value = spec
+        .default_value
+        .as_ref()
+        .map(|d| quote!(.default_value(#d)));
+    let env = spec.env.as_ref().map(|e| quote!(.env(#e)));
+    let multiple = matches!(spec.shape, Shape::Multiple).then(|| quote!(.multiple()));
+    let required = (matches!(spec.shape, Shape::Required) && spec.default_value.is_none())
+        .then(|| quote!(.required()));
+    let global = spec.global.then(|| quote!(.global()));
+    let hidden = spec.hidden.then(|| quote!(.hidden()));
+
+    Some(quote! {
+        ::seahorse::Flag::new(#name, ::seahorse::FlagType::#variant)
+            #description
+            #(.alias(#aliases))*
+            #default_value
+            #env
+            #multiple
+            #required
+            #global
+            #hidden
+    })
+}
+
+/// Local variable holding the field value,
+/// prefixed so that fields do not shadow the other locals of the generated code
+fn local_ident(ident: &Ident) -> Ident {
+    format_ident!("__seahorse_field_{}", ident)
+}
+
+/// Statement reading the field value into `Option` held by `local_ident` of the field
+fn value_tokens(spec: &FieldSpec) -> TokenStream2 {
+    let ident = local_ident(&spec.ident);
+    let name = &spec.name;
+    let flag = format!("--{}", name);
+    let getter = |suffix: &str| {
+        let (_, getter) = spec.kind.as_ref().unwrap();
+        Ident::new(
+            &format!("{}_flag{}", getter, suffix),
+            proc_macro2::Span::call_site(),
+        )
+    };
+
+    match spec.shape {
+        Shape::Bool => {
+            quote!(let #ident = ::std::option::Option::Some(__seahorse_c.bool_flag(#name));)
+        }
+        Shape::Required => {
+            let getter = getter("");
+            quote!(let #ident = __seahorse_errors.check(#flag, __seahorse_c.#getter(#name));)
+        }
+        Shape::Optional => {
+            let getter = getter("");
+            quote!(
+                let #ident = __seahorse_errors.check_optional(#flag, __seahorse_c.#getter(#name));
+            )
+        }
+        Shape::Multiple => {
+            let getter = getter("_vec");
+            quote!(let #ident = __seahorse_errors.check_vec(#flag, __seahorse_c.#getter(#name));)
+        }
+        Shape::Args => {
+            quote!(let #ident = ::std::option::Option::Some(__seahorse_c.args.clone());)
+        }
+    }
+}
+
+/// Get the first paragraph of the doc comment joined into a line
+fn doc_comment(attrs: &[Attribute]) -> Option<String> {
+    let lines = attrs
+        .iter()
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(nv) if nv.path.is_ident("doc") => match &nv.value {
+                Expr::Lit(expr) => match &expr.lit {
+                    Lit::Str(s) => Some(s.value().trim().to_string()),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        })
+        .skip_while(|line| line.is_empty())
+        .take_while(|line| !line.is_empty())
+        .collect::<Vec<_>>();
+
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join(" "))
+    }
+}
+
+/// Get `T` of the type like `Vec<T>`
+fn type_argument<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let segment = match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != wrapper {
+        return None;
+    }
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_type(ty: &Type, name: &str) -> bool {
+    match ty {
+        Type::Path(path) if path.qself.is_none() => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|s| s.ident == name && s.arguments.is_empty()),
+        _ => false,
+    }
+}
+
+/// Convert `DeployArgs` to `deploy-args`
+fn kebab_case(name: &str) -> String {
+    let mut out = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                out.push('-');
+            }
+            out.extend(c.to_lowercase());
+        } else if c == '_' {
+            out.push('-');
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
@@ -0,0 +1,117 @@
+use seahorse::{App, Context, FromContext, Seahorse};
+
+/// Deploy the app
+#[derive(Debug, PartialEq, Seahorse)]
+#[command(name = "deploy", alias = "d")]
+struct DeployArgs {
+    /// Target environment
+    #[flag(alias = "e")]
+    env: String,
+    #[flag(default_value = "8080")]
+    port: usize,
+    replicas: Option<isize>,
+    /// Skip confirmation
+    #[flag(alias = "y")]
+    yes: bool,
+    tag: Vec<String>,
+    #[args]
+    services: Vec<String>,
+}
+
+fn args(v: &[&str]) -> Vec<String> {
+    v.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn command_test() {
+    let command = DeployArgs::command();
+    assert_eq!(command.name, "deploy");
+    assert_eq!(command.description, Some("Deploy the app".to_string()));
+    assert_eq!(command.alias, Some(vec!["d".to_string()]));
+
+    let flags = command.flags.unwrap();
+    let names = flags.iter().map(|f| f.name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, vec!["env", "port", "replicas", "yes", "tag"]);
+    assert!(flags[0].required);
+    assert_eq!(flags[0].description, Some("Target environment".to_string()));
+    assert!(!flags[1].required);
+    assert!(!flags[2].required);
+    assert!(flags[4].multiple);
+}
+
+#[test]
+fn from_context_test() {
+    let action = |c: &Context| {
+        let args = c.parse_into::<DeployArgs>().unwrap();
+        assert_eq!(
+            args,
+            DeployArgs {
+                env: "prod".to_string(),
+                port: 8080,
+                replicas: Some(3),
+                yes: true,
+                tag: vec!["a".to_string(), "b".to_string()],
+                services: vec!["web".to_string(), "worker".to_string()],
+            }
+        );
+        Ok(())
+    };
+    let app = App::new("cli").command(DeployArgs::command().action_with_result(action));
+
+    let result = app.run_with_result(args(&[
+        "cli",
+        "deploy",
+        "-e",
+        "prod",
+        "--replicas",
+        "3",
+        "-y",
+        "--tag",
+        "a",
+        "--tag",
+        "b",
+        "web",
+        "worker",
+    ]));
+    assert!(result.is_ok());
+}
+
+#[test]
+fn from_context_errors_test() {
+    let context = Context::new(
+        args(&["--port", "x", "--replicas", "y"]),
+        DeployArgs::command().flags,
+        String::new(),
+    );
+
+    assert_eq!(
+        DeployArgs::from_context(&context).unwrap_err().to_string(),
+        "--env: not specified\n--port: invalid value\n--replicas: invalid value"
+    );
+}
+
+/// Fields named as the locals of the generated code
+#[derive(Debug, PartialEq, Seahorse)]
+struct ShadowArgs {
+    c: String,
+    errors: Option<isize>,
+    r#type: bool,
+}
+
+#[test]
+fn shadowing_fields_test() {
+    let context = Context::new(
+        args(&["--c", "x", "--errors", "1", "--type"]),
+        ShadowArgs::command().flags,
+        String::new(),
+    );
+
+    assert_eq!(
+        ShadowArgs::from_context(&context),
+        Ok(ShadowArgs {
+            c: "x".to_string(),
+            errors: Some(1),
+            r#type: true,
+        })
+    );
+}
@@ -1,9 +1,23 @@
-use crate::utils::normalized_args;
+use crate::color::Stream;
+use crate::completion::{
+    complete, complete_var, write_candidates, write_completions, write_registration,
+};
+use crate::context::validate_replaced_flags;
+use crate::help::{
+    error_text, example_help_text, grouped_command_help_text, grouped_flag_help_text,
+    has_visible_commands, usage_text, warn_deprecated_flags, wrap_description, HelpSections,
+};
+use crate::manpage::{write_manpage, write_manpages};
+use crate::markdown::write_markdown;
+use crate::utils::{command_index, normalized_args};
 use crate::{
-    error::ActionError, error::ActionErrorKind, Action, ActionWithResult, Command, Context, Flag,
-    FlagType, Help,
+    error::ActionError, error::ActionErrorKind, error::SpecError, Action, ActionWithResult, Arg,
+    ArgGroup, ColorChoice, Command, CommandSpec, Context, Flag, FlagType, Help, Scope, Shell,
 };
+use std::env;
 use std::error::Error;
+use std::io;
+use std::path::Path;
 
 /// Multiple action application entry point
 #[derive(Default)]
@@ -14,6 +28,8 @@ pub struct App {
     pub author: Option<String>,
     /// Application description
     pub description: Option<String>,
+    /// Application description shown in long help
+    pub long_description: Option<String>,
     /// Application usage
     pub usage: Option<String>,
     /// Application version
@@ -26,6 +42,34 @@ pub struct App {
     pub action_with_result: Option<ActionWithResult>,
     /// Application flags
     pub flags: Option<Vec<Flag>>,
+    /// Application positional arguments
+    pub args: Option<Vec<Arg>>,
+    /// Application flag groups
+    pub groups: Option<Vec<ArgGroup>>,
+    /// Width to wrap help text
+    pub help_width: Option<usize>,
+    /// When to color help and error output
+    pub color: ColorChoice,
+    /// Accept built-in `--color` flag
+    pub color_flag: bool,
+    /// Accept built-in `--debug-config` flag
+    pub debug_config_flag: bool,
+    /// Help text template with placeholders like `{name}`, `{usage}`, `{flags}` and `{commands}`
+    pub help_template: Option<String>,
+    /// Text printed before help
+    pub before_help: Option<String>,
+    /// Text printed after help
+    pub after_help: Option<String>,
+    /// Usage examples shown in help
+    pub examples: Option<Vec<String>>,
+    /// Path of the config file with default values of the flags
+    pub config_file: Option<String>,
+    /// Prefix of the environment variables read by all flags
+    pub env_prefix: Option<String>,
+    /// Accept built-in `completions <shell>` command
+    pub completions_command: bool,
+    /// Complete command lines when requested by the environment variable
+    pub dynamic_completions: bool,
 }
 
 impl App {
@@ -75,7 +119,25 @@ impl App {
         self
     }
 
+    /// Set long description of the app shown in `--help`
+    /// `-h` shows the description set by `App::description`
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli")
+    ///     .description("cli tool")
+    ///     .long_description("cli tool.\nLonger explanation shown with --help.");
+    /// ```
+    pub fn long_description<T: Into<String>>(mut self, description: T) -> Self {
+        self.long_description = Some(description.into());
+        self
+    }
+
     /// Set usage of the app
+    /// When not set, usage is built from the flags, arguments and sub commands
     ///
     /// Example
     ///
@@ -240,7 +302,431 @@ impl App {
         self
     }
 
+    /// Set positional argument of the app
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Arg};
+    ///
+    /// let app = App::new("cli")
+    ///     .arg(Arg::new("NAME").required())
+    ///     .arg(Arg::new("FILE").multiple());
+    /// ```
+    pub fn arg(mut self, arg: Arg) -> Self {
+        if let Some(ref mut args) = self.args {
+            (*args).push(arg);
+        } else {
+            self.args = Some(vec![arg]);
+        }
+        self
+    }
+
+    /// Set flag group of the app
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{ArgGroup, App, Flag, FlagType};
+    ///
+    /// let app = App::new("cli")
+    ///     .flag(Flag::new("json", FlagType::Bool))
+    ///     .flag(Flag::new("yaml", FlagType::Bool))
+    ///     .group(ArgGroup::new("format").flags(["json", "yaml"]).exclusive());
+    /// ```
+    pub fn group(mut self, group: ArgGroup) -> Self {
+        if let Some(ref mut groups) = self.groups {
+            (*groups).push(group);
+        } else {
+            self.groups = Some(vec![group]);
+        }
+        self
+    }
+
+    /// Set width to wrap help text
+    /// When not set, `COLUMNS` environment variable is used if available
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli")
+    ///     .help_width(80);
+    /// ```
+    pub fn help_width(mut self, width: usize) -> Self {
+        self.help_width = Some(width);
+        self
+    }
+
+    /// Set when to color help and error output
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, ColorChoice};
+    ///
+    /// let app = App::new("cli")
+    ///     .color(ColorChoice::Never);
+    /// ```
+    pub fn color(mut self, color: ColorChoice) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Accept built-in `--color <auto|always|never>` flag in the app and all commands,
+    /// which takes precedence over `App::color`
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli")
+    ///     .color_flag();
+    /// ```
+    pub fn color_flag(mut self) -> Self {
+        self.color_flag = true;
+        self.flag(
+            Flag::new("color", FlagType::String)
+                .description("When to color output: auto, always or never")
+                .no_env_prefix()
+                .global(),
+        )
+    }
+
+    /// Accept built-in `--debug-config` flag in the app and all commands,
+    /// which prints effective value and source of every flag instead of running the action
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli")
+    ///     .config_file("~/.config/cli/config")
+    ///     .debug_config_flag();
+    /// ```
+    pub fn debug_config_flag(mut self) -> Self {
+        self.debug_config_flag = true;
+        self.flag(
+            Flag::new("debug-config", FlagType::Bool)
+                .description("Show effective flag values and where they came from")
+                .no_env_prefix()
+                .global(),
+        )
+    }
+
+    /// Set help text template of the app
+    /// Available placeholders are `{name}`, `{author}`, `{description}`, `{usage}`, `{flags}`,
+    /// `{global_flags}`, `{commands}`, `{examples}`, `{version}`, `{before_help}` and `{after_help}`
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli")
+    ///     .help_template("{name}\n\nUSAGE:\n\t{usage}\n\nFLAGS:\n{flags}");
+    /// ```
+    pub fn help_template<T: Into<String>>(mut self, template: T) -> Self {
+        self.help_template = Some(template.into());
+        self
+    }
+
+    /// Set text printed before help of the app
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli")
+    ///     .before_help("cli is experimental");
+    /// ```
+    pub fn before_help<T: Into<String>>(mut self, text: T) -> Self {
+        self.before_help = Some(text.into());
+        self
+    }
+
+    /// Set text printed after help of the app
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli")
+    ///     .after_help("See https://example.com for details");
+    /// ```
+    pub fn after_help<T: Into<String>>(mut self, text: T) -> Self {
+        self.after_help = Some(text.into());
+        self
+    }
+
+    /// Set usage example of the app
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli")
+    ///     .example("cli John")
+    ///     .example("cli --bye John");
+    /// ```
+    pub fn example<T: Into<String>>(mut self, example: T) -> Self {
+        if let Some(ref mut examples) = self.examples {
+            (*examples).push(example.into());
+        } else {
+            self.examples = Some(vec![example.into()]);
+        }
+        self
+    }
+
+    /// Set config file with default values of the flags
+    /// Values are written as `key = value` with the flag name as key,
+    /// under `[command path]` sections for sub commands, e.g. `[remote add]`
+    /// Command line takes precedence over environment variables,
+    /// then the config file, then default values
+    /// Flags specified on the command line also drop the environment and config values
+    /// of the flags they conflict with, or share an exclusive group with
+    /// Missing config file is ignored
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli")
+    ///     .config_file("~/.config/cli/config");
+    /// ```
+    pub fn config_file<T: Into<String>>(mut self, path: T) -> Self {
+        self.config_file = Some(path.into());
+        self
+    }
+
+    /// Set prefix of the environment variables read by all flags when not specified,
+    /// named `<PREFIX>_<COMMAND>_<FLAG>` in upper case with dashes replaced by underscores
+    /// Global flags are named after the app or command defining them
+    /// Flags with `Flag::env` or `Flag::no_env_prefix` and the built-in flags are not affected
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// // `--token` of `mytool remote add` reads `MYTOOL_REMOTE_ADD_TOKEN`
+    /// let app = App::new("mytool")
+    ///     .env_prefix("MYTOOL");
+    /// ```
+    pub fn env_prefix<T: Into<String>>(mut self, prefix: T) -> Self {
+        self.env_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Accept built-in `completions <bash|zsh|fish>` command that prints completion script
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// // `cli completions bash > /etc/bash_completion.d/cli`
+    /// let app = App::new("cli")
+    ///     .completions_command();
+    /// ```
+    pub fn completions_command(mut self) -> Self {
+        self.completions_command = true;
+        self.command(
+            Command::new("completions")
+                .description("Print shell completion script")
+                .arg(Arg::new("SHELL").required()),
+        )
+    }
+
+    /// Complete command lines by the app itself when `<NAME>_COMPLETE` environment variable is set
+    /// to `bash`, `zsh` or `fish`, where `NAME` is `App::env_prefix` or the app name in upper case
+    /// `MYTOOL_COMPLETE=bash mytool` prints the script to register in the shell,
+    /// which calls `MYTOOL_COMPLETE=bash mytool -- <words>` to print candidates for the last word
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// // `source <(CLI_COMPLETE=bash cli)`
+    /// let app = App::new("cli")
+    ///     .dynamic_completions();
+    /// ```
+    pub fn dynamic_completions(mut self) -> Self {
+        self.dynamic_completions = true;
+        self
+    }
+
+    /// Write shell completion script of the app
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Shell};
+    ///
+    /// let app = App::new("cli");
+    /// app.generate_completions(Shell::Bash, &mut std::io::stdout())
+    ///     .unwrap();
+    /// ```
+    pub fn generate_completions<W: io::Write>(
+        &self,
+        shell: Shell,
+        writer: &mut W,
+    ) -> io::Result<()> {
+        write_completions(self, shell, writer)
+    }
+
+    /// Write man page of the app in roff format
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli");
+    /// app.render_manpage(&mut std::io::stdout())
+    ///     .unwrap();
+    /// ```
+    pub fn render_manpage<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        write_manpage(self, writer)
+    }
+
+    /// Write man pages of the app and every sub command into the directory,
+    /// named by the command path like `cli-remote-add.1`
+    ///
+    /// Example
+    ///
+    /// ```no_run
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli");
+    /// app.render_manpages("target/man")
+    ///     .unwrap();
+    /// ```
+    pub fn render_manpages<P: AsRef<Path>>(&self, dir: P) -> io::Result<()> {
+        write_manpages(self, dir.as_ref())
+    }
+
+    /// Write reference documentation of the app in Markdown,
+    /// with a page headed by the command path for the app and every sub command
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli");
+    /// app.render_markdown(&mut std::io::stdout())
+    ///     .unwrap();
+    /// ```
+    pub fn render_markdown<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        write_markdown(self, writer)
+    }
+
+    /// Get machine-readable spec of the app with commands, aliases, flags and arguments
+    /// Also printed as JSON by the hidden `--help-json` flag
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// let app = App::new("cli");
+    /// println!("{}", app.spec().to_json());
+    /// ```
+    pub fn spec(&self) -> CommandSpec {
+        CommandSpec::from(self)
+    }
+
+    /// Create new instance of `App` from JSON text in the format printed by `--help-json`
+    /// Bind actions afterwards with `bind_action` or `bind_action_with_result`
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Context};
+    ///
+    /// let spec = r#"{
+    ///     "name": "cli",
+    ///     "commands": [{"name": "hello", "flags": [{"name": "bye", "type": "bool"}]}]
+    /// }"#;
+    /// let app = App::from_spec(spec)
+    ///     .unwrap()
+    ///     .bind_action("hello", |c: &Context| println!("{:?}", c.args));
+    /// ```
+    pub fn from_spec(spec: &str) -> Result<Self, SpecError> {
+        CommandSpec::from_json(spec).map(Self::from)
+    }
+
+    /// Set action of the command at the space separated path, or of the app if the path is empty
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Command, Context};
+    ///
+    /// let app = App::new("cli")
+    ///     .command(Command::new("remote").command(Command::new("add")))
+    ///     .bind_action("remote add", |c: &Context| println!("{:?}", c.args));
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// The command must exist and not have action_with_result set.
+    ///
+    /// ```should_panic
+    /// use seahorse::{App, Context};
+    ///
+    /// let app = App::new("cli")
+    ///     .bind_action("remote", |c: &Context| println!("{:?}", c.args));
+    /// ```
+    pub fn bind_action(mut self, path: &str, action: Action) -> Self {
+        if path.trim().is_empty() {
+            return self.action(action);
+        }
+        let names = path.split_whitespace().collect::<Vec<_>>();
+        let command = command_at(&mut self.commands, &names)
+            .unwrap_or_else(|| panic!(r#"Command "{}" is not found."#, path));
+        *command = std::mem::take(command).action(action);
+        self
+    }
+
+    /// Set action_with_result of the command at the space separated path,
+    /// or of the app if the path is empty
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Command, Context};
+    ///
+    /// let app = App::new("cli")
+    ///     .command(Command::new("remote"))
+    ///     .bind_action_with_result("remote", |c: &Context| {println!("{:?}", c.args); Ok(())});
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// The command must exist and not have action set.
+    pub fn bind_action_with_result(mut self, path: &str, action: ActionWithResult) -> Self {
+        if path.trim().is_empty() {
+            return self.action_with_result(action);
+        }
+        let names = path.split_whitespace().collect::<Vec<_>>();
+        let command = command_at(&mut self.commands, &names)
+            .unwrap_or_else(|| panic!(r#"Command "{}" is not found."#, path));
+        *command = std::mem::take(command).action_with_result(action);
+        self
+    }
+
     /// Run app
+    /// Errors are printed to stderr before panicking with the error
     ///
     /// Example
     ///
@@ -253,10 +739,29 @@ impl App {
     /// app.run(args);
     /// ```
     pub fn run(&self, args: Vec<String>) {
-        match self.run_with_result(args) {
-            Ok(_) => return,
-            Err(e) => panic!("{}", e),
+        let scope = Scope {
+            error_color: self.color_choice(&args).enabled(Stream::Stderr),
+            ..Scope::default()
+        };
+        if let Err(e) = self.run_with_result(args) {
+            eprintln!("{}", error_text(&e.to_string(), &scope));
+            panic!("{}", e);
+        }
+    }
+
+    /// Get the color choice of the app, overridden by `--color` flag in `args` if accepted
+    pub(crate) fn color_choice(&self, args: &[String]) -> ColorChoice {
+        if self.color_flag {
+            if let Some(choice) = args
+                .iter()
+                .position(|a| a == "--color")
+                .and_then(|i| args.get(i + 1))
+                .and_then(|v| ColorChoice::from_name(v))
+            {
+                return choice;
+            }
         }
+        self.color
     }
 
     /// Run app, returning a result
@@ -272,62 +777,130 @@ impl App {
     /// let result = app.run_with_result(args);
     /// ```
     pub fn run_with_result(&self, args: Vec<String>) -> Result<(), Box<dyn Error>> {
+        if self.dynamic_completions {
+            if let Ok(name) = env::var(complete_var(self)) {
+                return self.run_completion(&name, &args);
+            }
+        }
+
         let args = normalized_args(args);
-        let (cmd_v, args_v) = match args.len() {
-            1 => args.split_at(1),
-            _ => args[1..].split_at(1),
-        };
+        let scope = Scope::new(self, &args);
+
+        if args.is_empty() {
+            self.help(&scope);
+            return Err(Box::new(ActionError {
+                kind: ActionErrorKind::NotFound,
+            }));
+        }
+
+        // Global flags may come before the sub command
+        let globals = self
+            .flags
+            .iter()
+            .flatten()
+            .filter(|f| f.global)
+            .collect::<Vec<_>>();
+        let index = 1 + command_index(&args[1..], &globals);
+
+        if let Some(cmd) = args.get(index) {
+            if self.completions_command && cmd == "completions" {
+                if let Some(name) = args.get(index + 1).filter(|a| !a.starts_with('-')) {
+                    return match Shell::from_name(name) {
+                        Some(shell) => Ok(self.generate_completions(shell, &mut io::stdout())?),
+                        None => Err(Box::new(ActionError {
+                            kind: ActionErrorKind::UnknownShell(name.to_string()),
+                        })),
+                    };
+                }
+            }
+
+            if let Some(command) = self.select_command(cmd) {
+                let mut args_v = args[1..index].to_vec();
+                args_v.extend_from_slice(&args[index + 1..]);
+                return command.run_with_scope(args_v, &scope.child(&self.name, &self.flags));
+            }
+        }
+
+        self.run_action(args[1..].to_vec(), &scope)
+    }
+
+    /// Run action of the app
+    /// Show help instead if no action is set or help flag is passed
+    /// `-h` shows the summary, `--help` shows long help and `--help-all` also shows hidden ones
+    /// `--help-json` prints the spec of the app as JSON
+    fn run_action(&self, args: Vec<String>, scope: &Scope) -> Result<(), Box<dyn Error>> {
+        if args.contains(&"--help-json".to_string()) {
+            println!("{}", self.spec().to_json());
+            return Ok(());
+        }
 
-        let cmd = match cmd_v.first() {
-            Some(c) => c,
+        if args.contains(&"--help-all".to_string()) {
+            self.help(&scope.help_all());
+            return Ok(());
+        }
+
+        if args.contains(&"--help".to_string()) {
+            self.help(&scope.long_help());
+            return Ok(());
+        }
+
+        if (self.action.is_none() && self.action_with_result.is_none())
+            || args.contains(&"-h".to_string())
+        {
+            self.help(scope);
+            return Ok(());
+        }
+
+        let flags = self.flags.clone().map(|f| scope.with_env_prefix(f, None));
+        warn_deprecated_flags(&flags, &args, scope);
+        validate_replaced_flags(&flags, &args)?;
+
+        let mut context = Context::parse(args, flags.clone(), self.help_text(scope), |f| {
+            scope.fallback_value(f, None)
+        });
+        context.drop_overridden_values(&flags, &self.groups);
+        if scope.debug_config && context.bool_flag("debug-config") {
+            print!("{}", context.value_sources_text());
+            return Ok(());
+        }
+        context.validate(&flags, &self.args, &self.groups)?;
+
+        match self.action {
+            Some(action) => {
+                action(&context);
+                Ok(())
+            }
+            None => match self.action_with_result {
+                Some(action_with_result) => action_with_result(&context),
+                None => Ok(()),
+            },
+        }
+    }
+
+    /// Print registration script of dynamic completion, or candidates for the words after `--`
+    fn run_completion(&self, name: &str, args: &[String]) -> Result<(), Box<dyn Error>> {
+        let shell = match Shell::from_name(name) {
+            Some(shell) => shell,
             None => {
-                self.help();
                 return Err(Box::new(ActionError {
-                    kind: ActionErrorKind::NotFound,
-                }));
+                    kind: ActionErrorKind::UnknownShell(name.to_string()),
+                }))
             }
         };
 
-        match self.select_command(cmd) {
-            Some(command) => return command.run_with_result(args_v.to_vec()),
-            None => match self.action {
-                Some(action) => {
-                    if args.contains(&"-h".to_string()) || args.contains(&"--help".to_string()) {
-                        self.help();
-                        return Ok(());
-                    }
-                    action(&Context::new(
-                        args[1..].to_vec(),
-                        self.flags.clone(),
-                        self.help_text(),
-                    ));
-                    return Ok(());
-                }
-                None => match self.action_with_result {
-                    Some(action_with_result) => {
-                        if args.contains(&"-h".to_string()) || args.contains(&"--help".to_string())
-                        {
-                            self.help();
-                            return Ok(());
-                        }
-                        return action_with_result(&Context::new(
-                            args[1..].to_vec(),
-                            self.flags.clone(),
-                            self.help_text(),
-                        ));
-                    }
-                    None => {
-                        self.help();
-                        return Ok(());
-                    }
-                },
-            },
+        match args.iter().position(|a| a == "--") {
+            Some(index) => {
+                let candidates = complete(self, &args[index + 1..]);
+                write_candidates(shell, &candidates, &mut io::stdout())?
+            }
+            None => write_registration(self, shell, &mut io::stdout())?,
         }
+        Ok(())
     }
 
     /// Select command
     /// Gets the Command that matches the string passed in the argument
-    fn select_command(&self, cmd: &str) -> Option<&Command> {
+    pub(crate) fn select_command(&self, cmd: &str) -> Option<&Command> {
         match &self.commands {
             Some(commands) => commands.iter().find(|command| match &command.alias {
                 Some(alias) => command.name == cmd || alias.iter().any(|a| a == cmd),
@@ -337,170 +910,83 @@ impl App {
         }
     }
 
-    fn flag_help_text(&self) -> String {
-        let mut text = String::new();
-        text += "Flags:\n";
-        let help_flag = "-h, --help";
-
-        if let Some(flags) = &self.flags {
-            let int_val = "<int>";
-            let float_val = "<float>";
-            let string_val = "<string>";
-
-            let flag_helps = &flags.iter().map(|f| {
-                let alias = match &f.alias {
-                    Some(alias) => alias
-                        .iter()
-                        .filter(|&a| a.len() == 1)
-                        .map(|a| format!("-{}", a))
-                        .collect::<Vec<String>>()
-                        .join(", "),
-                    None => String::new(),
-                };
-
-                let long_alias = match &f.alias {
-                    Some(alias) => alias
-                        .iter()
-                        .filter(|a| a.len() > 1)
-                        .map(|a| format!("--{}", a))
-                        .collect::<Vec<String>>()
-                        .join(", "),
-                    None => String::new(),
-                };
-
-                let val = match f.flag_type {
-                    FlagType::Int => int_val,
-                    FlagType::Float => float_val,
-                    FlagType::String => string_val,
-                    _ => "",
-                };
-
-                let help = if alias.is_empty() {
-                    if long_alias.is_empty() {
-                        format!("--{} {}", f.name, val)
-                    } else {
-                        format!("{}, --{}, {}", long_alias, f.name, val)
-                    }
-                } else {
-                    if long_alias.is_empty() {
-                        format!("{}, --{} {}", alias, f.name, val)
-                    } else {
-                        format!("{}, {}, --{} {}", alias, long_alias, f.name, val)
-                    }
-                };
-
-                (help, f.description.clone())
-            });
-
-            let flag_name_max_len = flag_helps
-                .clone()
-                .map(|h| h.0.len())
-                .chain(vec![help_flag.len()].into_iter())
-                .max()
-                .unwrap();
-
-            for flag_help in flag_helps.clone() {
-                text += &format!("\t{}", flag_help.0);
-
-                if let Some(usage) = &flag_help.1 {
-                    let flag_name_len = flag_help.0.len();
-                    text += &format!(
-                        "{} : {}\n",
-                        " ".repeat(flag_name_max_len - flag_name_len),
-                        usage
-                    );
-                } else {
-                    text += "\n";
-                }
-            }
-
-            text += &format!(
-                "\t{}{} : Show help\n",
-                help_flag,
-                " ".repeat(flag_name_max_len - help_flag.len())
-            );
-        } else {
-            text += &format!("\t{} : Show help\n", help_flag);
+    /// Get usage of the app, built from the definition when not set
+    pub(crate) fn usage_text(&self, scope: &Scope) -> String {
+        match &self.usage {
+            Some(usage) => usage.clone(),
+            None => usage_text(
+                &self.name,
+                &self.flags,
+                &self.args,
+                &self.groups,
+                has_visible_commands(&self.commands, scope),
+            ),
         }
-
-        text
     }
+}
 
-    fn command_help_text(&self) -> String {
-        let mut text = String::new();
-
-        if let Some(commands) = &self.commands {
-            text += "\nCommands:\n";
-
-            let name_max_len = &commands
-                .iter()
-                .map(|c| {
-                    if let Some(alias) = &c.alias {
-                        format!("{}, {}", alias.join(", "), c.name).len()
-                    } else {
-                        c.name.len()
-                    }
-                })
-                .max()
-                .unwrap();
-
-            for c in commands {
-                let command_name = if let Some(alias) = &c.alias {
-                    format!("{}, {}", alias.join(", "), c.name)
-                } else {
-                    c.name.clone()
-                };
-
-                let description = match &c.description {
-                    Some(description) => description,
-                    None => "",
-                };
-
-                text += &format!(
-                    "\t{} {}: {}\n",
-                    command_name,
-                    " ".repeat(name_max_len - command_name.len()),
-                    description
-                );
+impl Help for App {
+    fn help_text(&self, scope: &Scope) -> String {
+        let sections = HelpSections {
+            name: self.name.clone(),
+            author: self.author.clone(),
+            description: if scope.long_help {
+                self.long_description.as_ref().or(self.description.as_ref())
+            } else {
+                self.description.as_ref()
             }
-        }
+            .map(|d| wrap_description(d, 0, scope.width)),
+            usage: self.usage_text(scope),
+            flags: grouped_flag_help_text(
+                &scope.with_env_prefix(self.flags.clone().unwrap_or_default(), None),
+                scope,
+            ),
+            commands: grouped_command_help_text(
+                self.commands.as_deref().unwrap_or_default(),
+                scope,
+            ),
+            examples: if scope.long_help {
+                self.examples.as_deref().map(example_help_text)
+            } else {
+                None
+            },
+            version: self.version.clone(),
+            before_help: self.before_help.clone(),
+            after_help: self.after_help.clone(),
+            ..HelpSections::default()
+        };
 
-        text
+        sections.render(&self.help_template, scope)
     }
 }
 
-impl Help for App {
-    fn help_text(&self) -> String {
-        let mut text = String::new();
-
-        text += &format!("Name:\n\t{}\n\n", self.name);
-
-        if let Some(author) = &self.author {
-            text += &format!("Author:\n\t{}\n\n", author);
-        }
-
-        if let Some(description) = &self.description {
-            text += &format!("Description:\n\t{}\n\n", description);
-        }
-
-        if let Some(usage) = &self.usage {
-            text += &format!("Usage:\n\t{}\n\n", usage);
-        }
-
-        text += &self.flag_help_text();
-        text += &self.command_help_text();
-
-        if let Some(version) = &self.version {
-            text += &format!("\nVersion:\n\t{}\n", version);
-        }
-
-        text
+/// Find the command by the path of the command names
+fn command_at<'a>(
+    commands: &'a mut Option<Vec<Command>>,
+    names: &[&str],
+) -> Option<&'a mut Command> {
+    let (name, rest) = names.split_first()?;
+    let command = commands.iter_mut().flatten().find(|c| c.name == *name)?;
+    if rest.is_empty() {
+        Some(command)
+    } else {
+        command_at(&mut command.commands, rest)
     }
 }
 
 #[cfg(test)]
+#[allow(
+    clippy::assertions_on_constants,
+    clippy::bool_assert_comparison,
+    clippy::needless_return,
+    clippy::nonminimal_bool
+)]
 mod tests {
-    use crate::{Action, ActionWithResult, App, Command, Context, Flag, FlagType};
+    use crate::error::{ActionError, ActionErrorKind, FlagError};
+    use crate::{
+        Action, ActionWithResult, App, Arg, ArgGroup, ColorChoice, Command, Context, Flag,
+        FlagType, Help, Scope,
+    };
     use std::fmt;
 
     #[test]
@@ -804,6 +1290,561 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn required_test() {
+        let a: Action = |_: &Context| {};
+        let app = App::new("cli")
+            .action(a)
+            .flag(Flag::new("port", FlagType::Int).required())
+            .arg(Arg::new("NAME").required());
+
+        assert_eq!(
+            app.usage_text(&Scope::default()),
+            "cli [OPTIONS] --port <int> <NAME>"
+        );
+
+        let args = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        let result = app.run_with_result(args(&["cli", "john"]));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            r#"Required flag "--port" is not specified"#
+        );
+
+        let result = app.run_with_result(args(&["cli", "--port", "80"]));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Required argument <NAME> is not specified"
+        );
+
+        assert!(app
+            .run_with_result(args(&["cli", "--port", "80", "john"]))
+            .is_ok());
+    }
+
+    #[test]
+    fn group_test() {
+        let a: Action = |_: &Context| {};
+        let app = App::new("test")
+            .action(a)
+            .flag(Flag::new("json", FlagType::Bool))
+            .flag(Flag::new("yaml", FlagType::Bool))
+            .flag(Flag::new("table", FlagType::Bool))
+            .group(
+                ArgGroup::new("format")
+                    .flags(["json", "yaml", "table"])
+                    .exclusive()
+                    .required(),
+            );
+
+        assert_eq!(
+            app.usage_text(&Scope::default()),
+            "test [OPTIONS] <--json|--yaml|--table>"
+        );
+
+        let result = app.run_with_result(vec![
+            "test".to_string(),
+            "--json".to_string(),
+            "--table".to_string(),
+        ]);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            r#"Flags "--json" and "--table" cannot be used together"#
+        );
+
+        let result = app.run_with_result(vec!["test".to_string()]);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            r#"One of the flags "--json", "--yaml", "--table" must be specified"#
+        );
+
+        let result = app.run_with_result(vec!["test".to_string(), "--yaml".to_string()]);
+        assert!(result.is_ok());
+
+        let app = App::new("test")
+            .action(a)
+            .flag(Flag::new("all", FlagType::Bool))
+            .arg(Arg::new("NAMES").multiple())
+            .group(
+                ArgGroup::new("target")
+                    .flag("all")
+                    .arg("NAMES")
+                    .exclusive()
+                    .required(),
+            );
+        let args = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            app.usage_text(&Scope::default()),
+            "test [OPTIONS] <--all|NAMES...>"
+        );
+
+        let result = app.run_with_result(args(&["test", "--all", "a", "b"]));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            r#"Flag "--all" cannot be used with argument <NAMES>"#
+        );
+
+        let result = app.run_with_result(args(&["test"]));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            r#"One of the flags "--all" or arguments <NAMES> must be specified"#
+        );
+
+        assert!(app.run_with_result(args(&["test", "--all"])).is_ok());
+        assert!(app.run_with_result(args(&["test", "a", "b"])).is_ok());
+    }
+
+    #[test]
+    fn flag_rules_test() {
+        let a: Action = |_: &Context| {};
+        let app = App::new("test")
+            .action(a)
+            .flag(Flag::new("key", FlagType::String).requires("cert"))
+            .flag(Flag::new("cert", FlagType::String))
+            .flag(Flag::new("stdin", FlagType::Bool).conflicts_with("FILE"))
+            .flag(Flag::new("token", FlagType::String).required_unless("anonymous"))
+            .flag(Flag::new("anonymous", FlagType::Bool))
+            .arg(Arg::new("FILE").multiple());
+
+        let args = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        let result = app.run_with_result(args(&["test"]));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            r#"Required flag "--token" is not specified, unless "--anonymous" is used"#
+        );
+
+        let result = app.run_with_result(args(&["test", "--anonymous", "--key", "k"]));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            r#"Flag "--key" requires "--cert""#
+        );
+
+        let result = app.run_with_result(args(&["test", "--anonymous", "--stdin", "a.txt"]));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            r#"Flag "--stdin" cannot be used with argument <FILE>"#
+        );
+
+        let result = app.run_with_result(args(&["test", "--token", "t", "--stdin"]));
+        assert!(result.is_ok());
+
+        let app = App::new("test").action(a).flag(
+            Flag::new("token", FlagType::String)
+                .default_value("guest")
+                .required_unless("anonymous"),
+        );
+
+        assert!(app.run_with_result(args(&["test"])).is_ok());
+
+        let app = App::new("test")
+            .action(a)
+            .flag(Flag::new("stdin", FlagType::Bool).conflicts_with("FILE"))
+            .arg(Arg::new("NAME"))
+            .arg(Arg::new("FILE"));
+
+        let result = app.run_with_result(args(&["test", "--stdin", "john"]));
+        assert!(result.is_ok());
+
+        let result = app.run_with_result(args(&["test", "--stdin", "john", "a.txt"]));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            r#"Flag "--stdin" cannot be used with argument <FILE>"#
+        );
+    }
+
+    #[test]
+    fn config_file_test() {
+        let path =
+            std::env::temp_dir().join(format!("seahorse_config_file_test_{}", std::process::id()));
+        std::fs::write(
+            &path,
+            "host = file.example.com\n\
+             port = 8080\n\
+             verbose = yes\n\
+             json = true\n\
+             \n\
+             [remote add]\n\
+             name = origin\n",
+        )
+        .unwrap();
+        std::env::set_var("SEAHORSE_CONFIG_FILE_TEST_PORT", "9090");
+
+        let a: Action = |c: &Context| {
+            assert_eq!(c.string_flag("host"), Ok("cli.example.com".to_string()));
+            assert_eq!(c.int_flag("port"), Ok(9090));
+            assert_eq!(c.string_flag("user"), Ok("anonymous".to_string()));
+            assert!(c.bool_flag("verbose"));
+        };
+        let add_action: Action = |c: &Context| {
+            assert_eq!(c.string_flag("name"), Ok("origin".to_string()));
+            assert_eq!(c.string_flag("host"), Ok("file.example.com".to_string()));
+        };
+        let app = App::new("cli")
+            .action(a)
+            .config_file(path.to_str().unwrap())
+            .flag(Flag::new("host", FlagType::String).global())
+            .flag(Flag::new("port", FlagType::Int).env("SEAHORSE_CONFIG_FILE_TEST_PORT"))
+            .flag(Flag::new("user", FlagType::String).default_value("anonymous"))
+            .flag(Flag::new("verbose", FlagType::Bool))
+            .command(
+                Command::new("remote").command(
+                    Command::new("add")
+                        .action(add_action)
+                        .flag(Flag::new("name", FlagType::String)),
+                ),
+            );
+
+        app.run(vec![
+            "cli".to_string(),
+            "--host".to_string(),
+            "cli.example.com".to_string(),
+        ]);
+        app.run(vec![
+            "cli".to_string(),
+            "remote".to_string(),
+            "add".to_string(),
+        ]);
+
+        // Command line overrides the config value of the flag in the same exclusive group
+        let format_action: Action = |c: &Context| {
+            assert!(!c.bool_flag("json"));
+            assert!(c.bool_flag("yaml"));
+        };
+        let app = App::new("cli")
+            .action(format_action)
+            .config_file(path.to_str().unwrap())
+            .flag(Flag::new("json", FlagType::Bool))
+            .flag(Flag::new("yaml", FlagType::Bool))
+            .group(ArgGroup::new("format").flags(["json", "yaml"]).exclusive());
+        let result = app.run_with_result(vec!["cli".to_string(), "--yaml".to_string()]);
+        assert!(result.is_ok());
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn env_prefix_test() {
+        std::env::set_var("SEAHORSE_ENV_PREFIX_TEST_REMOTE_ADD_DRY_RUN", "1");
+        std::env::set_var("SEAHORSE_ENV_PREFIX_TEST_REMOTE_ADD_TOKEN", "secret");
+        std::env::set_var("SEAHORSE_ENV_PREFIX_TEST_VERBOSE", "1");
+
+        let add_action: Action = |c: &Context| {
+            assert!(c.bool_flag("dry-run"));
+            assert_eq!(c.string_flag("token"), Err(FlagError::NotFound));
+            assert!(c.bool_flag("verbose"));
+        };
+        let add = Command::new("add")
+            .action(add_action)
+            .flag(Flag::new("dry-run", FlagType::Bool))
+            .flag(Flag::new("token", FlagType::String).no_env_prefix());
+        let app = App::new("cli")
+            .env_prefix("SEAHORSE_ENV_PREFIX_TEST")
+            .color_flag()
+            .flag(Flag::new("verbose", FlagType::Bool).global())
+            .command(Command::new("remote").command(add));
+
+        app.run(vec![
+            "cli".to_string(),
+            "remote".to_string(),
+            "add".to_string(),
+        ]);
+
+        let help_text = app
+            .help_text(&Scope::new(&app, &[]).long_help())
+            .replace('\t', " ");
+        assert!(help_text.contains("[env: SEAHORSE_ENV_PREFIX_TEST_VERBOSE]"));
+        assert!(!help_text.contains("[env: SEAHORSE_ENV_PREFIX_TEST_COLOR]"));
+
+        let remote = &app.commands.as_ref().unwrap()[0];
+        let add = &remote.commands.as_ref().unwrap()[0];
+        let scope = Scope::new(&app, &[])
+            .child("cli", &app.flags)
+            .child("remote", &remote.flags)
+            .long_help();
+        let help_text = add.help_text(&scope).replace('\t', " ");
+        assert!(help_text.contains("[env: SEAHORSE_ENV_PREFIX_TEST_VERBOSE]"));
+        assert!(help_text.contains("[env: SEAHORSE_ENV_PREFIX_TEST_REMOTE_ADD_DRY_RUN]"));
+    }
+
+    #[test]
+    fn completions_command_test() {
+        let app = App::new("cli").completions_command();
+
+        let result = app.run_with_result(vec![
+            "cli".to_string(),
+            "completions".to_string(),
+            "bash".to_string(),
+        ]);
+        assert!(result.is_ok());
+
+        let result = app.run_with_result(vec![
+            "cli".to_string(),
+            "completions".to_string(),
+            "powershell".to_string(),
+        ]);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            r#"Unknown shell "powershell", expected bash, zsh or fish"#
+        );
+    }
+
+    #[test]
+    fn from_spec_test() {
+        let spec = r#"{
+            "name": "cli",
+            "version": "1.0.0",
+            "commands": [{
+                "name": "remote",
+                "commands": [{
+                    "name": "add",
+                    "aliases": ["a"],
+                    "flags": [{"name": "port", "type": "uint", "default_value": "22"}],
+                    "args": [{"name": "NAME", "required": true}]
+                }]
+            }]
+        }"#;
+        let action: ActionWithResult = |c: &Context| {
+            assert_eq!(c.uint_flag("port"), Ok(2222));
+            assert_eq!(c.args, vec!["origin".to_string()]);
+            Err(Box::new(ActionError {
+                kind: ActionErrorKind::NotFound,
+            }))
+        };
+        let app = App::from_spec(spec)
+            .unwrap()
+            .bind_action_with_result("remote add", action);
+
+        assert_eq!(app.version, Some("1.0.0".to_string()));
+        let result = app.run_with_result(vec![
+            "cli".to_string(),
+            "remote".to_string(),
+            "a".to_string(),
+            "--port".to_string(),
+            "2222".to_string(),
+            "origin".to_string(),
+        ]);
+        assert_eq!(result.unwrap_err().to_string(), "NotFound");
+
+        let add = &app.commands.as_ref().unwrap()[0].commands.as_ref().unwrap()[0];
+        assert!(add.args.as_ref().unwrap()[0].required);
+
+        assert_eq!(
+            App::from_spec("{\"name\": \"cli\",}")
+                .err()
+                .unwrap()
+                .to_string(),
+            "Invalid JSON at 1:16: expected a string key"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = r#"Command "remote push" is not found."#)]
+    fn bind_action_not_found_test() {
+        let action: Action = |c: &Context| println!("{:?}", c.args);
+        let _ = App::new("cli")
+            .command(Command::new("remote"))
+            .bind_action("remote push", action);
+    }
+
+    #[test]
+    fn usage_test() {
+        let add = Command::new("add")
+            .arg(Arg::new("NAME").required())
+            .arg(Arg::new("URL").required());
+        let remote = Command::new("remote").command(add);
+        let app = App::new("mytool").command(remote);
+
+        assert!(app
+            .help_text(&Scope::default())
+            .contains("Usage:\n\tmytool [OPTIONS] [COMMAND]\n"));
+
+        let remote = &app.commands.as_ref().unwrap()[0];
+        let add = &remote.commands.as_ref().unwrap()[0];
+        let scope = Scope::default()
+            .child("mytool", &None)
+            .child("remote", &None);
+        assert_eq!(
+            add.usage_text(&scope),
+            "mytool remote add [OPTIONS] <NAME> <URL>"
+        );
+    }
+
+    #[test]
+    fn global_flag_test() {
+        let a: Action = |c: &Context| {
+            assert!(c.bool_flag("verbose"));
+            assert_eq!(c.args, vec!["origin".to_string()]);
+        };
+        let add = Command::new("add").action(a);
+        let remote = Command::new("remote").command(add);
+        let app = App::new("mytool")
+            .action(|_: &Context| panic!("the sub command must run"))
+            .flag(Flag::new("verbose", FlagType::Bool).global())
+            .flag(Flag::new("host", FlagType::String).global())
+            .command(remote);
+
+        app.run(vec![
+            "mytool".to_string(),
+            "remote".to_string(),
+            "add".to_string(),
+            "origin".to_string(),
+            "--verbose".to_string(),
+        ]);
+        app.run(vec![
+            "mytool".to_string(),
+            "--host".to_string(),
+            "remote".to_string(),
+            "--verbose".to_string(),
+            "remote".to_string(),
+            "add".to_string(),
+            "origin".to_string(),
+        ]);
+        app.run(vec![
+            "mytool".to_string(),
+            "remote".to_string(),
+            "--verbose".to_string(),
+            "add".to_string(),
+            "origin".to_string(),
+        ]);
+
+        let remote = &app.commands.as_ref().unwrap()[0];
+        let add = &remote.commands.as_ref().unwrap()[0];
+        let scope = Scope::default()
+            .child("mytool", &app.flags)
+            .child("remote", &remote.flags);
+        let help_text = add.help_text(&scope);
+        assert!(help_text.starts_with("Name:\n\tmytool remote add\n"));
+        assert!(help_text.contains("Global Flags:\n\t--verbose \n"));
+    }
+
+    #[test]
+    fn color_test() {
+        let app = App::new("test").color(ColorChoice::Always).color_flag();
+
+        let scope = Scope::new(&app, &[]);
+        assert!(app.help_text(&scope).contains("\x1b[1;4mFlags:\x1b[0m\n"));
+        assert!(scope.error_color);
+
+        let scope = Scope::new(&app, &["--color".to_string(), "never".to_string()]);
+        assert!(app.help_text(&scope).contains("Flags:\n\t--color <string>"));
+    }
+
+    #[test]
+    fn help_template_test() {
+        let app = App::new("cli")
+            .version("1.0.0")
+            .before_help("before")
+            .after_help("after")
+            .example("cli John");
+
+        let help_text = app.help_text(&Scope::default().long_help());
+        assert!(help_text.starts_with("before\n\nName:\n\tcli\n"));
+        assert!(help_text.contains("\nExamples:\n\tcli John\n\nVersion:\n\t1.0.0\n"));
+        assert!(help_text.ends_with("\nafter\n"));
+
+        let app = app.help_template("{name} {version}\n{examples}");
+        assert_eq!(
+            app.help_text(&Scope::default().long_help()),
+            "cli 1.0.0\n\tcli John\n"
+        );
+    }
+
+    #[test]
+    fn long_help_test() {
+        let app = App::new("cli")
+            .description("short")
+            .long_description("long")
+            .example("cli John")
+            .flag(Flag::new("port", FlagType::Int).default_value("80"));
+
+        let help_text = app.help_text(&Scope::default());
+        assert!(help_text.contains("Description:\n\tshort\n"));
+        assert!(!help_text.contains("Examples:"));
+
+        let help_text = app.help_text(&Scope::default().long_help());
+        assert!(help_text.contains("Description:\n\tlong\n"));
+        assert!(help_text.contains("\t--port <int>\n\t        [default: 80]\n"));
+        assert!(help_text.contains("Examples:\n\tcli John\n"));
+    }
+
+    #[test]
+    fn default_value_test() {
+        let a: Action = |c: &Context| {
+            assert_eq!(c.int_flag("port"), Ok(80));
+            assert_eq!(c.string_flag("host"), Ok("example.com".to_string()));
+        };
+        let app = App::new("cli")
+            .action(a)
+            .flag(Flag::new("port", FlagType::Int).default_value("80"))
+            .flag(Flag::new("host", FlagType::String).default_value("localhost"));
+
+        app.run(vec![
+            "cli".to_string(),
+            "--host".to_string(),
+            "example.com".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn hidden_test() {
+        let a: Action = |c: &Context| assert!(c.bool_flag("debug"));
+        let app = App::new("cli")
+            .action(a)
+            .flag(Flag::new("debug", FlagType::Bool).hidden().global())
+            .command(Command::new("internal").hidden().action(a));
+
+        app.run(vec!["cli".to_string(), "--debug".to_string()]);
+        app.run(vec![
+            "cli".to_string(),
+            "internal".to_string(),
+            "--debug".to_string(),
+        ]);
+
+        let help_text = app.help_text(&Scope::default());
+        assert!(!help_text.contains("--debug"));
+        assert!(!help_text.contains("Commands:"));
+        assert!(help_text.contains("Usage:\n\tcli [OPTIONS]\n"));
+
+        let help_text = app.help_text(&Scope::default().help_all());
+        assert!(help_text.contains("--debug"));
+        assert!(help_text.contains("Commands:\n\tinternal"));
+        assert!(help_text.contains("Usage:\n\tcli [OPTIONS] [COMMAND]\n"));
+    }
+
+    #[test]
+    fn deprecated_help_test() {
+        let app = App::new("cli")
+            .flag(
+                Flag::new("out", FlagType::String)
+                    .description("Output file")
+                    .deprecated("use --output"),
+            )
+            .command(Command::new("rm").deprecated("use remove"));
+
+        let help_text = app.help_text(&Scope::default());
+        assert!(help_text.contains("Output file (deprecated: use --output)\n"));
+        assert!(help_text.contains("\trm : (deprecated: use remove)\n"));
+    }
+
+    #[test]
+    fn help_heading_test() {
+        let app = App::new("cli")
+            .flag(Flag::new("port", FlagType::Int).help_heading("Network"))
+            .flag(Flag::new("verbose", FlagType::Bool))
+            .flag(Flag::new("host", FlagType::String).help_heading("Network"))
+            .command(Command::new("remote").category("Management"))
+            .command(Command::new("hello"));
+
+        let help_text = app.help_text(&Scope::default());
+        assert!(help_text.contains(
+            "Flags:\n\t--verbose \n\t-h, --help : Show help\n\n\
+             Network:\n\t--port <int>\n\t--host <string>\n"
+        ));
+        assert!(help_text.contains("Commands:\n\thello : \n\nManagement:\n\tremote : \n"));
+    }
+
     #[derive(Debug, Clone)]
     struct Error;
 
@@ -0,0 +1,107 @@
+use crate::Completer;
+
+/// `Arg` type.
+///
+/// Positional argument struct
+#[derive(Clone, Debug)]
+pub struct Arg {
+    /// Argument name
+    pub name: String,
+    /// Required argument
+    pub required: bool,
+    /// Multiple occurrence
+    pub multiple: bool,
+    /// Source of dynamic completion candidates
+    pub completer: Option<Completer>,
+}
+
+impl Arg {
+    /// Create new instance of `Arg`
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Arg;
+    ///
+    /// let arg = Arg::new("NAME");
+    /// ```
+    pub fn new<T: Into<String>>(name: T) -> Self {
+        Self {
+            name: name.into(),
+            required: false,
+            multiple: false,
+            completer: None,
+        }
+    }
+
+    /// Set required argument, shown as `<NAME>` instead of `[NAME]` in the usage line
+    /// and checked before running the action
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Arg;
+    ///
+    /// let arg = Arg::new("NAME")
+    ///     .required();
+    /// ```
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Set multiple argument
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Arg;
+    ///
+    /// let arg = Arg::new("FILE")
+    ///     .multiple();
+    /// ```
+    pub fn multiple(mut self) -> Self {
+        self.multiple = true;
+        self
+    }
+
+    /// Set source of dynamic completion candidates
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Arg, Completer};
+    ///
+    /// let arg = Arg::new("DIR")
+    ///     .completer(Completer::Directories);
+    /// ```
+    pub fn completer(mut self, completer: Completer) -> Self {
+        self.completer = Some(completer);
+        self
+    }
+
+    /// Get argument placeholder for usage
+    pub(crate) fn placeholder(&self) -> String {
+        let dots = if self.multiple { "..." } else { "" };
+        if self.required {
+            format!("<{}>{}", self.name, dots)
+        } else {
+            format!("[{}]{}", self.name, dots)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Arg;
+
+    #[test]
+    fn placeholder_test() {
+        assert_eq!(Arg::new("NAME").placeholder(), "[NAME]");
+        assert_eq!(Arg::new("NAME").required().placeholder(), "<NAME>");
+        assert_eq!(
+            Arg::new("FILE").required().multiple().placeholder(),
+            "<FILE>..."
+        );
+    }
+}
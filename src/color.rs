@@ -0,0 +1,105 @@
+use std::env;
+use std::io::{self, IsTerminal};
+
+/// `ColorChoice` enum
+///
+/// When to color help and error output
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub enum ColorChoice {
+    /// Color if the output is a terminal and `NO_COLOR` environment variable is not set
+    #[default]
+    Auto,
+    /// Always color
+    Always,
+    /// Never color
+    Never,
+}
+
+impl ColorChoice {
+    /// Parse `--color` flag value
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "auto" => Some(ColorChoice::Auto),
+            "always" => Some(ColorChoice::Always),
+            "never" => Some(ColorChoice::Never),
+            _ => None,
+        }
+    }
+
+    /// Check whether output written to `stream` should be colored
+    pub(crate) fn enabled(self, stream: Stream) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                let no_color = env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+                let terminal = match stream {
+                    Stream::Stdout => io::stdout().is_terminal(),
+                    Stream::Stderr => io::stderr().is_terminal(),
+                };
+                !no_color && terminal
+            }
+        }
+    }
+}
+
+/// Output stream checked by `ColorChoice::Auto`
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Stream {
+    /// Help output
+    Stdout,
+    /// Error and warning output
+    Stderr,
+}
+
+/// Text style of help and error output
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Style {
+    /// Section header of help
+    Header,
+    /// Flag and command names
+    Literal,
+    /// Error prefix
+    Error,
+    /// Warning prefix
+    Warning,
+}
+
+/// Decorate text with ANSI escape sequence of the style if `color` is set
+pub(crate) fn paint(text: &str, style: Style, color: bool) -> String {
+    if !color {
+        return text.to_string();
+    }
+
+    let code = match style {
+        Style::Header => "1;4",
+        Style::Literal => "1;32",
+        Style::Error => "1;31",
+        Style::Warning => "1;33",
+    };
+
+    format!("\x1b[{}m{}\x1b[0m", code, text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{paint, ColorChoice, Stream, Style};
+
+    #[test]
+    fn color_choice_test() {
+        assert_eq!(ColorChoice::from_name("always"), Some(ColorChoice::Always));
+        assert_eq!(ColorChoice::from_name("rainbow"), None);
+        assert!(ColorChoice::Always.enabled(Stream::Stdout));
+        assert!(ColorChoice::Always.enabled(Stream::Stderr));
+        assert!(!ColorChoice::Never.enabled(Stream::Stderr));
+    }
+
+    #[test]
+    fn paint_test() {
+        assert_eq!(paint("error:", Style::Error, false), "error:");
+        assert_eq!(
+            paint("error:", Style::Error, true),
+            "\x1b[1;31merror:\x1b[0m"
+        );
+    }
+}
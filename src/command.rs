@@ -1,5 +1,11 @@
-use crate::utils::normalized_args;
-use crate::{Action, ActionWithResult, Context, Flag, FlagType, Help};
+use crate::context::validate_replaced_flags;
+use crate::help::{
+    example_help_text, flag_help_text, grouped_command_help_text, grouped_flag_help_text,
+    has_visible_commands, usage_text, warn_deprecated_flags, warning_text, wrap_description,
+    HelpSections,
+};
+use crate::utils::{command_index, normalized_args};
+use crate::{Action, ActionWithResult, Arg, ArgGroup, CommandSpec, Context, Flag, Help, Scope};
 use std::error::Error;
 
 /// Application command type
@@ -9,6 +15,8 @@ pub struct Command {
     pub name: String,
     /// Command description
     pub description: Option<String>,
+    /// Command description shown in long help
+    pub long_description: Option<String>,
     /// Command usage
     pub usage: Option<String>,
     /// Command action
@@ -20,6 +28,24 @@ pub struct Command {
     /// Command alias
     pub alias: Option<Vec<String>>,
     pub commands: Option<Vec<Command>>,
+    /// Command positional arguments
+    pub args: Option<Vec<Arg>>,
+    /// Command flag groups
+    pub groups: Option<Vec<ArgGroup>>,
+    /// Hidden from help
+    pub hidden: bool,
+    /// Deprecation note
+    pub deprecated: Option<String>,
+    /// Category to group the command under in help
+    pub category: Option<String>,
+    /// Help text template with placeholders like `{name}`, `{usage}`, `{flags}` and `{commands}`
+    pub help_template: Option<String>,
+    /// Text printed before help
+    pub before_help: Option<String>,
+    /// Text printed after help
+    pub after_help: Option<String>,
+    /// Usage examples shown in help
+    pub examples: Option<Vec<String>>,
 }
 
 impl Command {
@@ -54,7 +80,25 @@ impl Command {
         self
     }
 
+    /// Set long description of the command shown in `--help`
+    /// `-h` shows the description set by `Command::description`
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Command;
+    ///
+    /// let command = Command::new("cmd")
+    ///     .description("cmd tool")
+    ///     .long_description("cmd tool.\nLonger explanation shown with --help.");
+    /// ```
+    pub fn long_description<T: Into<String>>(mut self, description: T) -> Self {
+        self.long_description = Some(description.into());
+        self
+    }
+
     /// Set usage of the command
+    /// When not set, usage is built from the flags, arguments and sub commands
     ///
     /// Example
     ///
@@ -155,6 +199,94 @@ impl Command {
         self
     }
 
+    /// Set positional argument of the command
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Arg, Command};
+    ///
+    /// let command = Command::new("add")
+    ///     .arg(Arg::new("NAME").required())
+    ///     .arg(Arg::new("URL").required());
+    /// ```
+    pub fn arg(mut self, arg: Arg) -> Self {
+        if let Some(ref mut args) = self.args {
+            (*args).push(arg);
+        } else {
+            self.args = Some(vec![arg]);
+        }
+        self
+    }
+
+    /// Set flag group of the command
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{ArgGroup, Command, Flag, FlagType};
+    ///
+    /// let command = Command::new("list")
+    ///     .flag(Flag::new("json", FlagType::Bool))
+    ///     .flag(Flag::new("yaml", FlagType::Bool))
+    ///     .group(ArgGroup::new("format").flags(["json", "yaml"]).exclusive());
+    /// ```
+    pub fn group(mut self, group: ArgGroup) -> Self {
+        if let Some(ref mut groups) = self.groups {
+            (*groups).push(group);
+        } else {
+            self.groups = Some(vec![group]);
+        }
+        self
+    }
+
+    /// Set hidden command, which is dispatched as usual but not shown in help
+    /// Hidden commands are shown by `--help-all`
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Command;
+    ///
+    /// let command = Command::new("debug")
+    ///     .hidden();
+    /// ```
+    pub fn hidden(mut self) -> Self {
+        self.hidden = true;
+        self
+    }
+
+    /// Set deprecated command, which is dispatched as usual but prints a warning when its action runs
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Command;
+    ///
+    /// let command = Command::new("rm")
+    ///     .deprecated("use `remove` instead");
+    /// ```
+    pub fn deprecated<T: Into<String>>(mut self, note: T) -> Self {
+        self.deprecated = Some(note.into());
+        self
+    }
+
+    /// Set category to group the command under in help of the parent
+    /// Commands without category are shown under "Commands"
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Command;
+    ///
+    /// let command = Command::new("remote")
+    ///     .category("Management");
+    /// ```
+    pub fn category<T: Into<String>>(mut self, category: T) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
     /// Set alias of the command
     ///
     /// Example
@@ -233,7 +365,87 @@ impl Command {
         self
     }
 
-    fn select_command(&self, cmd: &str) -> Option<&Command> {
+    /// Set help text template of the command
+    /// Available placeholders are `{name}`, `{author}`, `{description}`, `{usage}`, `{flags}`,
+    /// `{global_flags}`, `{commands}`, `{examples}`, `{version}`, `{before_help}` and `{after_help}`
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Command;
+    ///
+    /// let command = Command::new("hello")
+    ///     .help_template("{name}\n\nUSAGE:\n\t{usage}\n\nFLAGS:\n{flags}");
+    /// ```
+    pub fn help_template<T: Into<String>>(mut self, template: T) -> Self {
+        self.help_template = Some(template.into());
+        self
+    }
+
+    /// Set text printed before help of the command
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Command;
+    ///
+    /// let command = Command::new("hello")
+    ///     .before_help("hello is experimental");
+    /// ```
+    pub fn before_help<T: Into<String>>(mut self, text: T) -> Self {
+        self.before_help = Some(text.into());
+        self
+    }
+
+    /// Set text printed after help of the command
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Command;
+    ///
+    /// let command = Command::new("hello")
+    ///     .after_help("See https://example.com for details");
+    /// ```
+    pub fn after_help<T: Into<String>>(mut self, text: T) -> Self {
+        self.after_help = Some(text.into());
+        self
+    }
+
+    /// Set usage example of the command
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Command;
+    ///
+    /// let command = Command::new("hello")
+    ///     .example("hello John")
+    ///     .example("hello --bye John");
+    /// ```
+    pub fn example<T: Into<String>>(mut self, example: T) -> Self {
+        if let Some(ref mut examples) = self.examples {
+            (*examples).push(example.into());
+        } else {
+            self.examples = Some(vec![example.into()]);
+        }
+        self
+    }
+
+    /// Get machine-readable spec of the command and its sub commands
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Command;
+    ///
+    /// let json = Command::new("hello").spec().to_json();
+    /// ```
+    pub fn spec(&self) -> CommandSpec {
+        CommandSpec::from(self)
+    }
+
+    pub(crate) fn select_command(&self, cmd: &str) -> Option<&Command> {
         match &self.commands {
             Some(commands) => commands.iter().find(|command| match &command.alias {
                 Some(alias) => command.name == cmd || alias.iter().any(|a| a == cmd),
@@ -246,231 +458,152 @@ impl Command {
     /// Run command
     /// Call this function only from `App`
     pub fn run_with_result(&self, args: Vec<String>) -> Result<(), Box<dyn Error>> {
+        self.run_with_scope(args, &Scope::default())
+    }
+
+    /// Run command in the scope of the parent commands
+    pub(crate) fn run_with_scope(
+        &self,
+        args: Vec<String>,
+        scope: &Scope,
+    ) -> Result<(), Box<dyn Error>> {
         let args = normalized_args(args);
 
-        match args.split_first() {
-            Some((cmd, args_v)) => match self.select_command(cmd) {
-                Some(command) => {
-                    return command.run_with_result(args_v.to_vec());
-                }
-                None => match self.action {
-                    Some(action) => {
-                        if args.contains(&"-h".to_string()) || args.contains(&"--help".to_string())
-                        {
-                            self.help();
-                            return Ok(());
-                        }
-                        action(&Context::new(
-                            args.to_vec(),
-                            self.flags.clone(),
-                            self.help_text(),
-                        ));
-                        return Ok(());
-                    }
-                    None => match self.action_with_result {
-                        Some(action_with_result) => {
-                            if args.contains(&"-h".to_string())
-                                || args.contains(&"--help".to_string())
-                            {
-                                self.help();
-                                return Ok(());
-                            }
-                            return action_with_result(&Context::new(
-                                args.to_vec(),
-                                self.flags.clone(),
-                                self.help_text(),
-                            ));
-                        }
-                        None => {
-                            self.help();
-                            return Ok(());
-                        }
-                    },
-                },
-            },
-            None => match self.action {
-                Some(action) => {
-                    if args.contains(&"-h".to_string()) || args.contains(&"--help".to_string()) {
-                        self.help();
-                        return Ok(());
-                    }
-                    action(&Context::new(
-                        args.to_vec(),
-                        self.flags.clone(),
-                        self.help_text(),
-                    ));
-                    return Ok(());
-                }
-                None => match self.action_with_result {
-                    Some(action_with_result) => {
-                        if args.contains(&"-h".to_string()) || args.contains(&"--help".to_string())
-                        {
-                            self.help();
-                            return Ok(());
-                        }
-                        return action_with_result(&Context::new(
-                            args.to_vec(),
-                            self.flags.clone(),
-                            self.help_text(),
-                        ));
-                    }
-                    None => {
-                        self.help();
-                        return Ok(());
-                    }
-                },
-            },
+        // Global flags may come before the sub command
+        let globals = scope
+            .flags
+            .iter()
+            .chain(self.flags.iter().flatten().filter(|f| f.global))
+            .collect::<Vec<_>>();
+        let index = command_index(&args, &globals);
+
+        if let Some(command) = args.get(index).and_then(|cmd| self.select_command(cmd)) {
+            let mut args_v = args[..index].to_vec();
+            args_v.extend_from_slice(&args[index + 1..]);
+            return command.run_with_scope(args_v, &scope.child(&self.name, &self.flags));
         }
+
+        self.run_action(args, scope)
     }
 
-    fn flag_help_text(&self) -> String {
-        let mut text = String::new();
-        text += "Flags:\n";
-        let help_flag = "-h, --help";
-
-        if let Some(flags) = &self.flags {
-            let int_val = "<int>";
-            let float_val = "<float>";
-            let string_val = "<string>";
-
-            let flag_helps = &flags.iter().map(|f| {
-                let alias = match &f.alias {
-                    Some(alias) => alias
-                        .iter()
-                        .filter(|a| a.len() == 1)
-                        .map(|a| format!("-{}", a))
-                        .collect::<Vec<String>>()
-                        .join(", "),
-                    None => String::new(),
-                };
-
-                let long_alias = match &f.alias {
-                    Some(alias) => alias
-                        .iter()
-                        .filter(|a| a.len() > 1)
-                        .map(|a| format!("--{}", a))
-                        .collect::<Vec<String>>()
-                        .join(", "),
-                    None => String::new(),
-                };
-
-                let val = match f.flag_type {
-                    FlagType::Int => int_val,
-                    FlagType::Float => float_val,
-                    FlagType::String => string_val,
-                    _ => "",
-                };
-
-                let help = if alias.is_empty() {
-                    if long_alias.is_empty() {
-                        format!("--{} {}", f.name, val)
-                    } else {
-                        format!("{}, --{}, {}", long_alias, f.name, val)
-                    }
-                } else {
-                    if long_alias.is_empty() {
-                        format!("{}, --{} {}", alias, f.name, val)
-                    } else {
-                        format!("{}, {}, --{} {}", alias, long_alias, f.name, val)
-                    }
-                };
-
-                (help, f.description.clone())
-            });
-
-            let flag_name_max_len = flag_helps
-                .clone()
-                .map(|h| h.0.len())
-                .chain(vec![help_flag.len()].into_iter())
-                .max()
-                .unwrap();
-
-            for flag_help in flag_helps.clone() {
-                text += &format!("\t{}", flag_help.0);
-
-                if let Some(usage) = &flag_help.1 {
-                    let flag_name_len = flag_help.0.len();
-                    text += &format!(
-                        "{} : {}\n",
-                        " ".repeat(flag_name_max_len - flag_name_len),
-                        usage
-                    );
-                } else {
-                    text += "\n";
-                }
-            }
+    /// Run action of the command
+    /// Show help instead if no action is set or help flag is passed
+    /// `-h` shows the summary, `--help` shows long help and `--help-all` also shows hidden ones
+    /// `--help-json` prints the spec of the command as JSON
+    fn run_action(&self, args: Vec<String>, scope: &Scope) -> Result<(), Box<dyn Error>> {
+        if args.contains(&"--help-json".to_string()) {
+            println!("{}", self.spec().to_json());
+            return Ok(());
+        }
 
-            text += &format!(
-                "\t{}{} : Show help\n",
-                help_flag,
-                " ".repeat(flag_name_max_len - help_flag.len())
-            );
-        } else {
-            text += &format!("\t{} : Show help\n", help_flag);
+        if args.contains(&"--help-all".to_string()) {
+            self.help(&scope.help_all());
+            return Ok(());
         }
 
-        text
-    }
+        if args.contains(&"--help".to_string()) {
+            self.help(&scope.long_help());
+            return Ok(());
+        }
 
-    fn command_help_text(&self) -> String {
-        let mut text = String::new();
+        if (self.action.is_none() && self.action_with_result.is_none())
+            || args.contains(&"-h".to_string())
+        {
+            self.help(scope);
+            return Ok(());
+        }
 
-        if let Some(commands) = &self.commands {
-            text += "\nCommands:\n";
+        if let Some(note) = &self.deprecated {
+            let message = format!(
+                r#"command "{}" is deprecated: {}"#,
+                scope.command_path(&self.name),
+                note
+            );
+            eprintln!("{}", warning_text(&message, scope));
+        }
 
-            let name_max_len = &commands
-                .iter()
-                .map(|c| {
-                    if let Some(alias) = &c.alias {
-                        format!("{}, {}", alias.join(", "), c.name).len()
-                    } else {
-                        c.name.len()
-                    }
-                })
-                .max()
-                .unwrap();
-
-            for c in commands {
-                let command_name = if let Some(alias) = &c.alias {
-                    format!("{}, {}", alias.join(", "), c.name)
-                } else {
-                    c.name.clone()
-                };
-
-                let description = match &c.description {
-                    Some(description) => description,
-                    None => "",
-                };
-
-                text += &format!(
-                    "\t{} {}: {}\n",
-                    command_name,
-                    " ".repeat(name_max_len - command_name.len()),
-                    description
-                );
+        let flags = scope
+            .flags_with(&self.flags)
+            .map(|f| scope.with_env_prefix(f, Some(&self.name)));
+        warn_deprecated_flags(&flags, &args, scope);
+        validate_replaced_flags(&flags, &args)?;
+
+        let mut context = Context::parse(args, flags.clone(), self.help_text(scope), |f| {
+            scope.fallback_value(f, Some(&self.name))
+        });
+        context.drop_overridden_values(&flags, &self.groups);
+        if scope.debug_config && context.bool_flag("debug-config") {
+            print!("{}", context.value_sources_text());
+            return Ok(());
+        }
+        context.validate(&flags, &self.args, &self.groups)?;
+
+        match self.action {
+            Some(action) => {
+                action(&context);
+                Ok(())
             }
+            None => match self.action_with_result {
+                Some(action_with_result) => action_with_result(&context),
+                None => Ok(()),
+            },
         }
+    }
 
-        text
+    /// Get usage of the command, built from the definition when not set
+    pub(crate) fn usage_text(&self, scope: &Scope) -> String {
+        match &self.usage {
+            Some(usage) => usage.clone(),
+            None => usage_text(
+                &scope.command_path(&self.name),
+                &self.flags,
+                &self.args,
+                &self.groups,
+                has_visible_commands(&self.commands, scope),
+            ),
+        }
     }
 }
 
 impl Help for Command {
-    fn help_text(&self) -> String {
-        let mut text = String::new();
-
-        if let Some(description) = &self.description {
-            text += &format!("Description:\n\t{}\n\n", description);
-        }
-
-        if let Some(usage) = &self.usage {
-            text += &format!("Usage:\n\t{}\n\n", usage);
-        }
-
-        text += &self.flag_help_text();
-        text += &self.command_help_text();
+    fn help_text(&self, scope: &Scope) -> String {
+        let global_flags =
+            scope.with_env_prefix(scope.inherited_flags(&self.flags), Some(&self.name));
+
+        let sections = HelpSections {
+            name: scope.command_path(&self.name),
+            description: if scope.long_help {
+                self.long_description.as_ref().or(self.description.as_ref())
+            } else {
+                self.description.as_ref()
+            }
+            .map(|d| wrap_description(d, 0, scope.width)),
+            usage: self.usage_text(scope),
+            flags: grouped_flag_help_text(
+                &scope.with_env_prefix(self.flags.clone().unwrap_or_default(), Some(&self.name)),
+                scope,
+            ),
+            global_flags: Some(flag_help_text(
+                &global_flags.iter().collect::<Vec<_>>(),
+                false,
+                scope,
+            ))
+            .filter(|text| !text.is_empty()),
+            commands: grouped_command_help_text(
+                self.commands.as_deref().unwrap_or_default(),
+                scope,
+            ),
+            examples: if scope.long_help {
+                self.examples.as_deref().map(example_help_text)
+            } else {
+                None
+            },
+            before_help: self.before_help.clone(),
+            after_help: self.after_help.clone(),
+            ..HelpSections::default()
+        };
 
-        text
+        sections.render(&self.help_template, scope)
     }
 }
 
@@ -0,0 +1,720 @@
+use crate::reference::{flag_names, visible_commands, visible_flags};
+use crate::utils::normalized_args;
+use crate::{App, Arg, Command, Flag, FlagType, Scope};
+use std::fs;
+use std::io;
+
+/// `Shell` enum
+///
+/// Shells supported by completion script generation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    /// Get shell from the name passed to the built-in `completions` command
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "bash" => Some(Shell::Bash),
+            "zsh" => Some(Shell::Zsh),
+            "fish" => Some(Shell::Fish),
+            _ => None,
+        }
+    }
+}
+
+/// `Completer` enum
+///
+/// Source of dynamic completion candidates for values of flags and positional arguments
+#[derive(Clone, Debug)]
+pub enum Completer {
+    /// File and directory paths
+    Files,
+    /// Directory paths
+    Directories,
+    /// Fixed values
+    Values(Vec<String>),
+    /// Host names from the list, keeping `user@` typed before the host name
+    Hosts(Vec<String>),
+    /// Callback called with the partial value
+    Custom(fn(&str) -> Vec<String>),
+}
+
+impl Completer {
+    /// Get candidates for the partial value
+    pub(crate) fn complete(&self, current: &str) -> Vec<String> {
+        match self {
+            Completer::Files => path_candidates(current, false),
+            Completer::Directories => path_candidates(current, true),
+            Completer::Values(values) => values
+                .iter()
+                .filter(|v| v.starts_with(current))
+                .cloned()
+                .collect(),
+            Completer::Hosts(hosts) => {
+                let (user, host) = match current.rsplit_once('@') {
+                    Some((user, host)) => (format!("{}@", user), host),
+                    None => (String::new(), current),
+                };
+                hosts
+                    .iter()
+                    .filter(|h| h.starts_with(host))
+                    .map(|h| format!("{}{}", user, h))
+                    .collect()
+            }
+            Completer::Custom(complete) => complete(current),
+        }
+    }
+}
+
+/// Command of the tree to complete, with the flags accepted including inherited ones
+struct Node<'a> {
+    /// Command path without the app name, empty for the app
+    path: String,
+    flags: Vec<Flag>,
+    commands: Vec<&'a Command>,
+}
+
+/// Write completion script of the app for the shell
+pub(crate) fn write_completions<W: io::Write>(
+    app: &App,
+    shell: Shell,
+    writer: &mut W,
+) -> io::Result<()> {
+    let nodes = completion_nodes(app);
+    let script = match shell {
+        Shell::Bash => bash_script(app, &nodes),
+        Shell::Zsh => zsh_script(app, &nodes),
+        Shell::Fish => fish_script(app, &nodes),
+    };
+    writer.write_all(script.as_bytes())
+}
+
+/// Walk the commands tree of the app
+fn completion_nodes(app: &App) -> Vec<Node<'_>> {
+    let mut nodes = vec![Node {
+        path: String::new(),
+        flags: visible_flags(&app.flags),
+        commands: visible_commands(&app.commands),
+    }];
+
+    let scope = Scope::default().child(&app.name, &app.flags);
+    for command in visible_commands(&app.commands) {
+        collect_nodes(command, "", &scope, &mut nodes);
+    }
+    nodes
+}
+
+fn collect_nodes<'a>(command: &'a Command, parent: &str, scope: &Scope, nodes: &mut Vec<Node<'a>>) {
+    let path = join_path(parent, &command.name);
+    nodes.push(Node {
+        path: path.clone(),
+        flags: visible_flags(&scope.flags_with(&command.flags)),
+        commands: visible_commands(&command.commands),
+    });
+
+    let scope = scope.child(&command.name, &command.flags);
+    for sub in visible_commands(&command.commands) {
+        collect_nodes(sub, &path, &scope, nodes);
+    }
+}
+
+fn join_path(parent: &str, name: &str) -> String {
+    if parent.is_empty() {
+        name.to_string()
+    } else {
+        format!("{} {}", parent, name)
+    }
+}
+
+/// Get the command name and its aliases
+fn command_names(command: &Command) -> Vec<&str> {
+    let mut names = vec![command.name.as_str()];
+    names.extend(command.alias.iter().flatten().map(|a| a.as_str()));
+    names
+}
+
+/// Get the first line of the description
+fn summary(description: &Option<String>) -> &str {
+    description
+        .as_deref()
+        .and_then(|d| d.lines().next())
+        .unwrap_or_default()
+}
+
+/// Get the shell function name for the app
+fn function_name(app: &App) -> String {
+    app.name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// Quote text with single quotes for bash and zsh
+fn sh_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
+
+/// Quote text with single quotes for fish
+fn fish_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\\', r"\\").replace('\'', r"\'"))
+}
+
+/// Build shell `case` loop that resolves the command path from the words typed so far
+fn path_loop(nodes: &[Node], words: &str, start: usize, end: &str) -> String {
+    let mut script = format!(
+        "    for ((i = {}; i < {}; i++)); do\n        case \"${{cmd_path}}:${{{}[i]}}\" in\n",
+        start, end, words
+    );
+    for node in nodes {
+        for command in &node.commands {
+            let patterns = command_names(command)
+                .iter()
+                .map(|name| format!("\"{}:{}\"", node.path, name))
+                .collect::<Vec<_>>()
+                .join(" | ");
+            script += &format!(
+                "            {})\n                cmd_path=\"{}\"\n                ;;\n",
+                patterns,
+                join_path(&node.path, &command.name)
+            );
+        }
+    }
+    script + "        esac\n    done\n\n"
+}
+
+/// Build shell `case` branch that stops completion after flags taking a value
+fn value_flags_case(node: &Node, prev: &str, action: &str) -> String {
+    let patterns = node
+        .flags
+        .iter()
+        .filter(|f| f.flag_type != FlagType::Bool)
+        .map(|f| flag_names(f).join(" | "))
+        .collect::<Vec<_>>();
+    if patterns.is_empty() {
+        return String::new();
+    }
+
+    format!(
+        "            case \"{}\" in\n                {})\n{}                    \
+         return\n                    ;;\n            esac\n",
+        prev,
+        patterns.join(" | "),
+        action
+    )
+}
+
+fn bash_script(app: &App, nodes: &[Node]) -> String {
+    let function = function_name(app);
+    let mut script = format!(
+        "_{}() {{\n    local cur prev opts cmd_path=\"\" i\n    \
+         cur=\"${{COMP_WORDS[COMP_CWORD]}}\"\n    \
+         prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"\n\n",
+        function
+    );
+    if nodes.len() > 1 {
+        script += &path_loop(nodes, "COMP_WORDS", 1, "COMP_CWORD");
+    }
+
+    script += "    case \"${cmd_path}\" in\n";
+    for node in nodes {
+        let mut opts = node.flags.iter().flat_map(flag_names).collect::<Vec<_>>();
+        opts.extend(["-h".to_string(), "--help".to_string()]);
+        for command in &node.commands {
+            opts.extend(command_names(command).iter().map(|n| n.to_string()));
+        }
+
+        script += &format!("        \"{}\")\n", node.path);
+        script += &value_flags_case(node, "${prev}", "");
+        script += &format!("            opts=\"{}\"\n            ;;\n", opts.join(" "));
+    }
+    script += "    esac\n\n";
+
+    script
+        + &format!(
+            "    COMPREPLY=($(compgen -W \"${{opts}}\" -- \"${{cur}}\"))\n}}\n\n\
+             complete -o default -F _{} {}\n",
+            function, app.name
+        )
+}
+
+fn zsh_script(app: &App, nodes: &[Node]) -> String {
+    let function = function_name(app);
+    let mut script = format!(
+        "#compdef {}\n\n_{}() {{\n    local cmd_path=\"\" i\n\n",
+        app.name, function
+    );
+    if nodes.len() > 1 {
+        script += &path_loop(nodes, "words", 2, "CURRENT");
+    }
+
+    script += "    case \"${cmd_path}\" in\n";
+    for node in nodes {
+        script += &format!("        \"{}\")\n", node.path);
+        script += &value_flags_case(node, "${words[CURRENT-1]}", "                    _files\n");
+
+        if !node.commands.is_empty() {
+            script += "            local -a commands=(\n";
+            for command in &node.commands {
+                for name in command_names(command) {
+                    script += &format!(
+                        "                {}\n",
+                        sh_quote(&format!("{}:{}", name, summary(&command.description)))
+                    );
+                }
+            }
+            script += "            )\n            _describe -t commands 'command' commands\n";
+        }
+
+        script += "            local -a flags=(\n";
+        for flag in &node.flags {
+            for name in flag_names(flag) {
+                script += &format!(
+                    "                {}\n",
+                    sh_quote(&format!("{}:{}", name, summary(&flag.description)))
+                );
+            }
+        }
+        script += "                '-h:Show help'\n                '--help:Show help'\n";
+        script += "            )\n            _describe -t flags 'flag' flags\n            ;;\n";
+    }
+    script += "    esac\n}\n\n";
+
+    script
+        + &format!(
+            "if [ \"$funcstack[1]\" = \"_{0}\" ]; then\n    _{0} \"$@\"\n\
+             else\n    compdef _{0} {1}\nfi\n",
+            function, app.name
+        )
+}
+
+fn fish_script(app: &App, nodes: &[Node]) -> String {
+    let function = format!("__fish_{}_using_path", function_name(app));
+    let mut script = format!(
+        "function {}\n    set -l cmd_path \"\"\n    set -l words (commandline -opc)\n    \
+         set -e words[1]\n    for word in $words\n        switch \"$cmd_path:$word\"\n",
+        function
+    );
+    for node in nodes {
+        for command in &node.commands {
+            let patterns = command_names(command)
+                .iter()
+                .map(|name| format!("\"{}:{}\"", node.path, name))
+                .collect::<Vec<_>>()
+                .join(" ");
+            script += &format!(
+                "            case {}\n                set cmd_path \"{}\"\n",
+                patterns,
+                join_path(&node.path, &command.name)
+            );
+        }
+    }
+    script += "        end\n    end\n    test \"$cmd_path\" = \"$argv[1]\"\nend\n\n";
+    script += &format!("complete -c {} -f\n", app.name);
+
+    for node in nodes {
+        let condition = fish_quote(&format!("{} \"{}\"", function, node.path));
+        for command in &node.commands {
+            for name in command_names(command) {
+                script += &format!(
+                    "complete -c {} -n {} -a {} -d {}\n",
+                    app.name,
+                    condition,
+                    name,
+                    fish_quote(summary(&command.description))
+                );
+            }
+        }
+
+        for flag in &node.flags {
+            let mut options = format!("-l {}", flag.name);
+            for alias in flag.alias.iter().flatten() {
+                if alias.len() > 1 {
+                    options += &format!(" -l {}", alias);
+                } else {
+                    options += &format!(" -s {}", alias);
+                }
+            }
+            if flag.flag_type != FlagType::Bool {
+                options += " -r -F";
+            }
+            script += &format!(
+                "complete -c {} -n {} {} -d {}\n",
+                app.name,
+                condition,
+                options,
+                fish_quote(summary(&flag.description))
+            );
+        }
+        script += &format!(
+            "complete -c {} -n {} -s h -l help -d 'Show help'\n",
+            app.name, condition
+        );
+    }
+
+    script
+}
+
+/// Get name of the environment variable that requests dynamic completion,
+/// e.g. `MYTOOL_COMPLETE` for the app named `mytool`
+pub(crate) fn complete_var(app: &App) -> String {
+    let prefix = match &app.env_prefix {
+        Some(prefix) => prefix.clone(),
+        None => function_name(app).to_uppercase(),
+    };
+    format!("{}_COMPLETE", prefix)
+}
+
+/// Get completion candidates with descriptions for the words typed after the app name,
+/// the last of which is the partial word being completed
+pub(crate) fn complete(app: &App, words: &[String]) -> Vec<(String, String)> {
+    let (current, typed) = match words.split_last() {
+        Some((current, typed)) => (current.as_str(), normalized_args(typed.to_vec())),
+        None => ("", Vec::new()),
+    };
+
+    // Resolve the command by the leading words in the same way as dispatching
+    let mut rest = typed.as_slice();
+    let mut scope = Scope::default().child(&app.name, &app.flags);
+    let mut flags = visible_flags(&app.flags);
+    let mut args = &app.args;
+    let mut commands = visible_commands(&app.commands);
+    let mut next = rest.first().and_then(|w| app.select_command(w));
+    while let Some(command) = next {
+        rest = &rest[1..];
+        flags = visible_flags(&scope.flags_with(&command.flags));
+        args = &command.args;
+        commands = visible_commands(&command.commands);
+        next = rest.first().and_then(|w| command.select_command(w));
+        scope = scope.child(&command.name, &command.flags);
+    }
+
+    // Values of the flags without completer are left to the shell
+    if let Some(flag) = rest.last().and_then(|prev| value_flag(&flags, prev)) {
+        return match flag.value_completer() {
+            Some(completer) => completer
+                .complete(current)
+                .into_iter()
+                .map(|v| (v, String::new()))
+                .collect(),
+            None => Vec::new(),
+        };
+    }
+
+    let mut candidates = Vec::new();
+    if current.starts_with('-') {
+        for flag in &flags {
+            let description = summary(&flag.description);
+            candidates.extend(
+                flag_names(flag)
+                    .into_iter()
+                    .map(|n| (n, description.to_string())),
+            );
+        }
+        candidates.push(("-h".to_string(), "Show help".to_string()));
+        candidates.push(("--help".to_string(), "Show help".to_string()));
+    } else {
+        if rest.is_empty() {
+            for command in &commands {
+                let description = summary(&command.description);
+                candidates.extend(
+                    command_names(command)
+                        .iter()
+                        .map(|n| (n.to_string(), description.to_string())),
+                );
+            }
+        }
+
+        let completer =
+            positional_arg(args, positional_count(rest, &flags)).and_then(|a| a.completer.as_ref());
+        if let Some(completer) = completer {
+            candidates.extend(
+                completer
+                    .complete(current)
+                    .into_iter()
+                    .map(|v| (v, String::new())),
+            );
+        }
+    }
+
+    candidates.retain(|(name, _)| name.starts_with(current));
+    candidates
+}
+
+/// Get the flag taking a value written as `word`
+fn value_flag<'a>(flags: &'a [Flag], word: &str) -> Option<&'a Flag> {
+    flags
+        .iter()
+        .find(|f| f.flag_type != FlagType::Bool && f.option_index(&[word.to_string()]).is_some())
+}
+
+/// Count positional arguments in the words, skipping flags and their values
+fn positional_count(words: &[String], flags: &[Flag]) -> usize {
+    let mut count = 0;
+    let mut skip_value = false;
+    for word in words {
+        if skip_value {
+            skip_value = false;
+        } else if word.starts_with('-') {
+            skip_value = value_flag(flags, word).is_some();
+        } else {
+            count += 1;
+        }
+    }
+    count
+}
+
+/// Get the positional argument at the index, where the last one can be multiple
+fn positional_arg(args: &Option<Vec<Arg>>, index: usize) -> Option<&Arg> {
+    let args = args.as_ref()?;
+    args.get(index)
+        .or_else(|| args.last().filter(|a| a.multiple))
+}
+
+/// Get paths starting with the partial path, with `/` after directory names
+/// Hidden files are included only when the partial name starts with `.`
+fn path_candidates(current: &str, dirs_only: bool) -> Vec<String> {
+    let (dir, prefix) = match current.rfind('/') {
+        Some(i) => current.split_at(i + 1),
+        None => ("", current),
+    };
+    let entries = match fs::read_dir(if dir.is_empty() { "." } else { dir }) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut candidates = entries
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let name = entry.file_name().into_string().ok()?;
+            if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+                return None;
+            }
+            let is_dir = entry.path().is_dir();
+            if dirs_only && !is_dir {
+                return None;
+            }
+            Some(format!("{}{}{}", dir, name, if is_dir { "/" } else { "" }))
+        })
+        .collect::<Vec<_>>();
+    candidates.sort();
+    candidates
+}
+
+/// Write completion candidates one per line in the format the shell reads
+pub(crate) fn write_candidates<W: io::Write>(
+    shell: Shell,
+    candidates: &[(String, String)],
+    writer: &mut W,
+) -> io::Result<()> {
+    for (name, description) in candidates {
+        match shell {
+            Shell::Bash => writeln!(writer, "{}", name)?,
+            Shell::Zsh if description.is_empty() => {
+                writeln!(writer, "{}", name.replace(':', r"\:"))?
+            }
+            Shell::Zsh => writeln!(writer, "{}:{}", name.replace(':', r"\:"), description)?,
+            Shell::Fish => writeln!(writer, "{}\t{}", name, description)?,
+        }
+    }
+    Ok(())
+}
+
+/// Write script that registers the app binary itself as the completion source of the shell
+pub(crate) fn write_registration<W: io::Write>(
+    app: &App,
+    shell: Shell,
+    writer: &mut W,
+) -> io::Result<()> {
+    let function = function_name(app);
+    let var = complete_var(app);
+    let script = match shell {
+        Shell::Bash => format!(
+            "_{0}_complete() {{\n    local IFS=$'\\n'\n    \
+             COMPREPLY=($({1}=bash \"${{COMP_WORDS[0]}}\" -- \"${{COMP_WORDS[@]:1:COMP_CWORD}}\"))\n\
+             }}\n\ncomplete -o default -F _{0}_complete {2}\n",
+            function, var, app.name
+        ),
+        Shell::Zsh => format!(
+            "#compdef {2}\n\n_{0}_complete() {{\n    local -a candidates\n    \
+             candidates=(\"${{(@f)$({1}=zsh \"${{words[1]}}\" -- \"${{(@)words[2,CURRENT]}}\")}}\")\n    \
+             _describe 'values' candidates || _files\n}}\n\ncompdef _{0}_complete {2}\n",
+            function, var, app.name
+        ),
+        Shell::Fish => format!(
+            "function __fish_{0}_complete\n    set -l words (commandline -opc) (commandline -ct)\n    \
+             set -e words[1]\n    {1}=fish {2} -- $words\nend\n\n\
+             complete -c {2} -f -a '(__fish_{0}_complete)'\n",
+            function, var, app.name
+        ),
+    };
+    writer.write_all(script.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{complete, complete_var, write_candidates, write_completions};
+    use crate::{App, Arg, Command, Completer, Flag, FlagType, Shell};
+
+    fn app() -> App {
+        App::new("cli")
+            .flag(
+                Flag::new("name", FlagType::String)
+                    .alias("n")
+                    .description("Name to greet"),
+            )
+            .flag(
+                Flag::new("verbose", FlagType::Bool)
+                    .description("Show details")
+                    .global(),
+            )
+            .flag(Flag::new("debug", FlagType::Bool).hidden())
+            .command(
+                Command::new("remote")
+                    .alias("r")
+                    .description("Manage remotes")
+                    .command(
+                        Command::new("add")
+                            .description("Add a remote")
+                            .flag(Flag::new("url", FlagType::String).description("Remote's URL")),
+                    ),
+            )
+            .command(Command::new("internal").hidden())
+    }
+
+    fn completions(shell: Shell) -> String {
+        let mut buf = Vec::new();
+        write_completions(&app(), shell, &mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn bash_test() {
+        assert_eq!(
+            completions(Shell::Bash),
+            include_str!("snapshots/completion.bash")
+        );
+    }
+
+    #[test]
+    fn zsh_test() {
+        assert_eq!(
+            completions(Shell::Zsh),
+            include_str!("snapshots/completion.zsh")
+        );
+    }
+
+    #[test]
+    fn fish_test() {
+        assert_eq!(
+            completions(Shell::Fish),
+            include_str!("snapshots/completion.fish")
+        );
+    }
+
+    #[test]
+    fn complete_test() {
+        let complete = |words: &[&str]| {
+            let words = words.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+            complete(&app(), &words)
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(complete(&[""]), vec!["remote", "r"]);
+        assert_eq!(complete(&["re"]), vec!["remote"]);
+        assert_eq!(complete(&["r", ""]), vec!["add"]);
+        assert_eq!(
+            complete(&["remote", "add", "--"]),
+            vec!["--url", "--verbose", "--help"]
+        );
+        assert_eq!(
+            complete(&["remote", "add", "--url", ""]),
+            Vec::<String>::new()
+        );
+        assert_eq!(complete(&["remote", "add", "x", ""]), Vec::<String>::new());
+        assert_eq!(
+            complete(&["-"]),
+            vec!["--name", "-n", "--verbose", "-h", "--help"]
+        );
+    }
+
+    #[test]
+    fn write_candidates_test() {
+        let candidates = vec![("add".to_string(), "Add a remote".to_string())];
+        let write = |shell| {
+            let mut buf = Vec::new();
+            write_candidates(shell, &candidates, &mut buf).unwrap();
+            String::from_utf8(buf).unwrap()
+        };
+
+        assert_eq!(write(Shell::Bash), "add\n");
+        assert_eq!(write(Shell::Zsh), "add:Add a remote\n");
+        assert_eq!(write(Shell::Fish), "add\tAdd a remote\n");
+        assert_eq!(complete_var(&App::new("my-tool")), "MY_TOOL_COMPLETE");
+        assert_eq!(
+            complete_var(&App::new("cli").env_prefix("MYTOOL")),
+            "MYTOOL_COMPLETE"
+        );
+    }
+
+    #[test]
+    fn completer_test() {
+        let hosts = Completer::Hosts(vec!["example.com".to_string(), "example.org".to_string()]);
+        assert_eq!(hosts.complete("root@example.o"), vec!["root@example.org"]);
+        assert_eq!(hosts.complete("ex").len(), 2);
+        assert_eq!(
+            Completer::Directories.complete("src/snap"),
+            vec!["src/snapshots/"]
+        );
+        assert_eq!(
+            Completer::Files.complete("src/snapshots/completion.b"),
+            vec!["src/snapshots/completion.bash"]
+        );
+        assert_eq!(
+            Completer::Custom(|current| vec![format!("{}1", current)]).complete("v"),
+            vec!["v1"]
+        );
+    }
+
+    #[test]
+    fn complete_values_test() {
+        let app = App::new("cli").command(
+            Command::new("deploy")
+                .flag(Flag::new("format", FlagType::String).possible_values(["json", "yaml"]))
+                .flag(
+                    Flag::new("host", FlagType::String)
+                        .completer(Completer::Hosts(vec!["example.com".to_string()])),
+                )
+                .arg(Arg::new("ENV").completer(Completer::Values(vec![
+                    "staging".to_string(),
+                    "production".to_string(),
+                ]))),
+        );
+        let complete = |words: &[&str]| {
+            let words = words.iter().map(|w| w.to_string()).collect::<Vec<_>>();
+            complete(&app, &words)
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(complete(&["deploy", "--format", "y"]), vec!["yaml"]);
+        assert_eq!(
+            complete(&["deploy", "--host", "me@"]),
+            vec!["me@example.com"]
+        );
+        assert_eq!(
+            complete(&["deploy", "--format", "json", "st"]),
+            vec!["staging"]
+        );
+        assert_eq!(
+            complete(&["deploy", "production", ""]),
+            Vec::<String>::new()
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use std::env;
+use std::fs;
+use std::io;
+
+/// Value in the config file
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct ConfigEntry {
+    /// Command path of the section, empty for the app
+    pub section: String,
+    pub key: String,
+    pub value: String,
+    /// Line number starting from 1
+    pub line: usize,
+}
+
+/// Parsed config file with `key = value` lines and `[command path]` sections
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Config {
+    pub path: String,
+    pub entries: Vec<ConfigEntry>,
+    /// Messages of the lines that could not be parsed
+    pub errors: Vec<String>,
+}
+
+impl Config {
+    /// Load config file, expanding leading `~/` to the home directory
+    /// Missing file is not an error and results in `None`
+    pub fn load(path: &str) -> Result<Option<Self>, io::Error> {
+        let path = match (path.strip_prefix("~/"), env::var("HOME")) {
+            (Some(rest), Ok(home)) => format!("{}/{}", home.trim_end_matches('/'), rest),
+            _ => path.to_string(),
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(content) => Ok(Some(Self::parse(&path, &content))),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Parse content of the config file
+    pub fn parse(path: &str, content: &str) -> Self {
+        let mut config = Self {
+            path: path.to_string(),
+            ..Self::default()
+        };
+        let mut section = String::new();
+
+        for (i, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = name.split_whitespace().collect::<Vec<_>>().join(" ");
+                continue;
+            }
+
+            match line.split_once('=') {
+                Some((key, value)) if !key.trim().is_empty() => {
+                    config.entries.push(ConfigEntry {
+                        section: section.clone(),
+                        key: key.trim().to_string(),
+                        value: unquote(value.trim()).to_string(),
+                        line: i + 1,
+                    });
+                }
+                _ => config.errors.push(format!(
+                    "{}:{}: expected \"key = value\" or \"[section]\"",
+                    path,
+                    i + 1
+                )),
+            }
+        }
+
+        config
+    }
+
+    /// Get value of `key` in the section of the command path
+    /// Values in the sections of the parent commands apply to sub commands as well
+    pub fn get(&self, path: &[String], key: &str) -> Option<&ConfigEntry> {
+        (0..=path.len()).rev().find_map(|i| {
+            let section = path[..i].join(" ");
+            self.entries
+                .iter()
+                .rev()
+                .find(|e| e.section == section && e.key == key)
+        })
+    }
+}
+
+/// Remove matching quotes around the value
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if value.len() >= 2 && value.starts_with(quote) && value.ends_with(quote) {
+            return &value[1..value.len() - 1];
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Config;
+
+    #[test]
+    fn parse_test() {
+        let config = Config::parse(
+            "config",
+            "# comment\n\
+             verbose = true\n\
+             host = \"example.com\"\n\
+             \n\
+             [remote  add]\n\
+             host = remote.example.com\n\
+             invalid line\n",
+        );
+        let path = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(config.get(&[], "host").unwrap().value, "example.com");
+        assert_eq!(config.get(&[], "host").unwrap().line, 3);
+        assert_eq!(
+            config.get(&path(&["remote", "add"]), "host").unwrap().value,
+            "remote.example.com"
+        );
+        assert_eq!(
+            config
+                .get(&path(&["remote", "add"]), "verbose")
+                .unwrap()
+                .value,
+            "true"
+        );
+        assert_eq!(
+            config.get(&path(&["remote"]), "host").unwrap().value,
+            "example.com"
+        );
+        assert!(config.get(&[], "port").is_none());
+        assert_eq!(
+            config.errors,
+            vec!["config:7: expected \"key = value\" or \"[section]\"".to_string()]
+        );
+    }
+}
@@ -1,5 +1,8 @@
-use crate::error::FlagError;
-use crate::{Flag, FlagType, FlagValue};
+use crate::error::{ActionError, ActionErrorKind, ContextError, FlagError};
+use crate::utils::display_width;
+use crate::{Arg, ArgGroup, Flag, FlagType, FlagValue, FromContext};
+use std::fmt;
+use std::str::FromStr;
 
 /// `Context` type
 ///
@@ -9,21 +12,79 @@ pub struct Context {
     pub args: Vec<String>,
     /// `Vec` that stores flag name and flag value as tuple
     flags: Option<Vec<(String, Result<FlagValue, FlagError>)>>,
+    /// `Vec` that stores flag name and where the flag value came from as tuple
+    sources: Vec<(String, ValueSource)>,
     help_text: String,
 }
 
+/// Where the flag value came from
+#[derive(PartialEq, Clone, Debug)]
+pub enum ValueSource {
+    /// Specified on the command line
+    CommandLine,
+    /// Read from the environment variable
+    Environment(String),
+    /// Read from the config file at the path and line
+    ConfigFile(String, usize),
+    /// Default value, or no value
+    Default,
+}
+
+impl fmt::Display for ValueSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValueSource::CommandLine => f.write_str("command line"),
+            ValueSource::Environment(var) => write!(f, "env {}", var),
+            ValueSource::ConfigFile(path, line) => write!(f, "config {}:{}", path, line),
+            ValueSource::Default => f.write_str("default"),
+        }
+    }
+}
+
 impl Context {
     /// Create new instance of `Context`
     /// Parse processing using `Vec<String>` command line argument and `Vec<Flag>` as arguments
     pub fn new(args: Vec<String>, flags: Option<Vec<Flag>>, help_text: String) -> Self {
+        Self::parse(args, flags, help_text, |_| None)
+    }
+
+    /// Parse command line arguments, reading values of the flags not specified from `fallback`
+    /// before falling back to the default values
+    pub(crate) fn parse<F>(
+        args: Vec<String>,
+        flags: Option<Vec<Flag>>,
+        help_text: String,
+        fallback: F,
+    ) -> Self
+    where
+        F: Fn(&Flag) -> Option<(String, ValueSource)>,
+    {
         let mut v = Vec::new();
+        let mut sources = Vec::new();
         let mut parsed_args = args;
+
+        // Rewrite deprecated flags to their replacements before parsing,
+        // unless the replacement is not defined and the flag keeps its own value
+        for flag in flags.iter().flatten() {
+            if let Some(replacement) = &flag.replaced_by {
+                let defined = flags.iter().flatten().any(|f| &f.name == replacement);
+                if replacement == &flag.name || !defined {
+                    continue;
+                }
+                while let Some(index) = flag.option_index(&parsed_args) {
+                    parsed_args[index] = format!("--{}", replacement);
+                }
+            }
+        }
         let flags_val = match flags {
             Some(flags) => {
-                for flag in flags {
+                for flag in &flags {
                     let mut found_flag = false;
                     loop {
                         if let Some(index) = flag.option_index(&parsed_args) {
+                            if !found_flag {
+                                sources.push((flag.name.to_string(), ValueSource::CommandLine));
+                            }
                             found_flag = true;
                             parsed_args.remove(index);
 
@@ -42,12 +103,54 @@ impl Context {
                             }
                         } else {
                             if !found_flag || !flag.multiple {
-                                v.push((flag.name.to_string(), Err(FlagError::NotFound)));
+                                let fallback_val = fallback(flag).and_then(|(value, source)| {
+                                    fallback_flag_value(flag, value).map(|val| (val, source))
+                                });
+                                let val = match fallback_val {
+                                    Some((val, source)) => {
+                                        sources.push((flag.name.to_string(), source));
+                                        val
+                                    }
+                                    None => default_flag_value(flag),
+                                };
+                                if sources.iter().all(|(name, _)| name != &flag.name) {
+                                    sources.push((flag.name.to_string(), ValueSource::Default));
+                                }
+                                v.push((flag.name.to_string(), val));
                             }
                             break;
                         }
                     }
                 }
+
+                // Conditional defaults are evaluated once explicit values are known
+                for flag in &flags {
+                    let specified = sources.iter().any(|(name, source)| {
+                        name == &flag.name && source != &ValueSource::Default
+                    });
+                    if flag.flag_type == FlagType::Bool || specified {
+                        continue;
+                    }
+
+                    let default = flag
+                        .default_value_if
+                        .iter()
+                        .flatten()
+                        .find(|(other, value, _)| {
+                            flags.iter().filter(|f| &f.name == other).any(|other| {
+                                v.iter().any(|(name, val)| {
+                                    name == &other.name && condition_matches(other, value, val)
+                                })
+                            })
+                        })
+                        .map(|(_, _, default)| default.clone());
+
+                    if let Some(default) = default {
+                        if let Some(entry) = v.iter_mut().find(|(name, _)| name == &flag.name) {
+                            entry.1 = flag.value(Some(default));
+                        }
+                    }
+                }
                 Some(v)
             }
             None => None,
@@ -56,10 +159,252 @@ impl Context {
         Self {
             args: parsed_args,
             flags: flags_val,
+            sources,
             help_text,
         }
     }
 
+    /// Drop values read from the environment variables or config file
+    /// when a flag they conflict with, or share an exclusive group with, is specified on the command line,
+    /// so that the command line overrides them instead of failing as conflicting flags
+    pub(crate) fn drop_overridden_values(
+        &mut self,
+        flags: &Option<Vec<Flag>>,
+        groups: &Option<Vec<ArgGroup>>,
+    ) {
+        let mut sets = flags
+            .iter()
+            .flatten()
+            .filter_map(|f| {
+                let conflicts = f.conflicts_with.as_ref()?;
+                Some(
+                    std::iter::once(&f.name)
+                        .chain(conflicts)
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        sets.extend(
+            groups
+                .iter()
+                .flatten()
+                .filter(|g| g.exclusive)
+                .map(|g| g.flags.iter().collect()),
+        );
+
+        for set in sets {
+            let on_command_line =
+                |name: &str| self.value_source(name) == Some(ValueSource::CommandLine);
+            if !set.iter().any(|name| on_command_line(name)) {
+                continue;
+            }
+
+            let dropped = set
+                .into_iter()
+                .filter(|name| !on_command_line(name) && self.is_present(name))
+                .collect::<Vec<_>>();
+            for flag in flags
+                .iter()
+                .flatten()
+                .filter(|f| dropped.contains(&&f.name))
+            {
+                if let Some(values) = &mut self.flags {
+                    values.retain(|(name, _)| name != &flag.name);
+                    values.push((flag.name.clone(), default_flag_value(flag)));
+                }
+                for (name, source) in &mut self.sources {
+                    if name == &flag.name {
+                        *source = ValueSource::Default;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Check that required flags and positional arguments are specified
+    /// and flag rules and groups are satisfied
+    pub(crate) fn validate(
+        &self,
+        flags: &Option<Vec<Flag>>,
+        args: &Option<Vec<Arg>>,
+        groups: &Option<Vec<ArgGroup>>,
+    ) -> Result<(), ActionError> {
+        for flag in flags.iter().flatten().filter(|f| f.required) {
+            if let Err(FlagError::NotFound) = self.result_flag_value(&flag.name) {
+                return Err(ActionError {
+                    kind: ActionErrorKind::MissingRequiredFlag(flag.name.clone()),
+                });
+            }
+        }
+
+        if let Some(arg) = args
+            .iter()
+            .flatten()
+            .find(|a| a.required && !self.is_arg_present(&a.name, args))
+        {
+            return Err(ActionError {
+                kind: ActionErrorKind::MissingRequiredArg(arg.name.clone()),
+            });
+        }
+
+        for flag in flags.iter().flatten() {
+            self.validate_rules(flag, args)?;
+        }
+
+        for group in groups.iter().flatten() {
+            let flags = group
+                .flags
+                .iter()
+                .filter(|name| self.is_present(name))
+                .collect::<Vec<_>>();
+            let args = group
+                .args
+                .iter()
+                .filter(|name| self.is_arg_present(name, args))
+                .collect::<Vec<_>>();
+
+            if group.exclusive && flags.len() + args.len() > 1 {
+                let kind = match (flags.as_slice(), args.as_slice()) {
+                    ([a, b, ..], _) => {
+                        ActionErrorKind::ConflictingFlags(a.to_string(), b.to_string())
+                    }
+                    ([flag], [arg, ..]) => {
+                        ActionErrorKind::ConflictingArg(flag.to_string(), arg.to_string())
+                    }
+                    (_, [a, b, ..]) => {
+                        ActionErrorKind::ConflictingArgs(a.to_string(), b.to_string())
+                    }
+                    _ => unreachable!(),
+                };
+                return Err(ActionError { kind });
+            }
+
+            if group.required && flags.is_empty() && args.is_empty() {
+                return Err(ActionError {
+                    kind: ActionErrorKind::MissingRequiredGroup(
+                        group.flags.clone(),
+                        group.args.clone(),
+                    ),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check `requires`, `conflicts_with` and `required_unless` rules of the flag
+    fn validate_rules(&self, flag: &Flag, args: &Option<Vec<Arg>>) -> Result<(), ActionError> {
+        if let Some(unless) = &flag.required_unless {
+            let resolved = !matches!(self.result_flag_value(&flag.name), Err(FlagError::NotFound));
+            if !resolved && !unless.iter().any(|name| self.is_present(name)) {
+                return Err(ActionError {
+                    kind: ActionErrorKind::MissingRequiredUnless(flag.name.clone(), unless.clone()),
+                });
+            }
+        }
+
+        if !self.is_present(&flag.name) {
+            return Ok(());
+        }
+
+        if let Some(name) = flag
+            .requires
+            .iter()
+            .flatten()
+            .find(|name| !self.is_present(name))
+        {
+            return Err(ActionError {
+                kind: ActionErrorKind::MissingRequiredBy(flag.name.clone(), name.clone()),
+            });
+        }
+
+        for name in flag.conflicts_with.iter().flatten() {
+            if self.is_present(name) {
+                return Err(ActionError {
+                    kind: ActionErrorKind::ConflictingFlags(flag.name.clone(), name.clone()),
+                });
+            }
+
+            if self.is_arg_present(name, args) {
+                return Err(ActionError {
+                    kind: ActionErrorKind::ConflictingArg(flag.name.clone(), name.clone()),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check whether a value is passed in the position of the argument named `name`,
+    /// or any trailing value if it is the last argument accepting multiple values
+    fn is_arg_present(&self, name: &str, args: &Option<Vec<Arg>>) -> bool {
+        args.iter()
+            .flatten()
+            .position(|arg| arg.name == name)
+            .is_some_and(|index| self.args.len() > index)
+    }
+
+    /// Check whether the flag is specified on the command line, environment variable or config file
+    pub(crate) fn is_present(&self, name: &str) -> bool {
+        matches!(self.value_source(name), Some(source) if source != ValueSource::Default)
+    }
+
+    /// Get where the flag value came from
+    /// Returns `None` if the flag is not defined
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Context, ValueSource};
+    ///
+    /// fn action(c: &Context) {
+    ///     match c.value_source("token") {
+    ///         Some(ValueSource::Environment(var)) => println!("token is read from ${}", var),
+    ///         Some(source) => println!("token is read from {}", source),
+    ///         None => println!("token is not defined"),
+    ///     }
+    /// }
+    /// ```
+    pub fn value_source(&self, name: &str) -> Option<ValueSource> {
+        self.sources
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, source)| source.clone())
+    }
+
+    /// Build text listing effective value and source of every flag, used by `--debug-config`
+    pub(crate) fn value_sources_text(&self) -> String {
+        let name_max_len = self
+            .sources
+            .iter()
+            .map(|(name, _)| display_width(name))
+            .max()
+            .unwrap_or(0);
+
+        self.sources
+            .iter()
+            .map(|(name, source)| {
+                let values = self
+                    .result_flag_value_vec(name)
+                    .iter()
+                    .map(|r| match r {
+                        Ok(val) => value_text(val),
+                        Err(FlagError::NotFound) => "(not set)".to_string(),
+                        Err(e) => format!("({})", e),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!(
+                    "{}{} = {} [{}]\n",
+                    name,
+                    " ".repeat(name_max_len - display_width(name)),
+                    values,
+                    source
+                )
+            })
+            .collect()
+    }
+
     /// Get flag value
     fn result_flag_value(&self, name: &str) -> Result<FlagValue, FlagError> {
         let flag = self
@@ -134,7 +479,7 @@ impl Context {
 
         r.iter()
             .map(|r| match r {
-                Ok(FlagValue::Bool(val)) => Ok(val.clone()),
+                Ok(FlagValue::Bool(val)) => Ok(*val),
                 Err(FlagError::NotFound) => Err(FlagError::NotFound),
                 _ => Err(FlagError::TypeError),
             })
@@ -234,7 +579,7 @@ impl Context {
 
         r.iter()
             .map(|r| match r {
-                Ok(FlagValue::Int(val)) => Ok(val.clone()),
+                Ok(FlagValue::Int(val)) => Ok(*val),
                 Err(FlagError::NotFound) => Err(FlagError::NotFound),
                 _ => Err(FlagError::TypeError),
             })
@@ -284,7 +629,7 @@ impl Context {
 
         r.iter()
             .map(|r| match r {
-                Ok(FlagValue::Uint(val)) => Ok(val.clone()),
+                Ok(FlagValue::Uint(val)) => Ok(*val),
                 Err(FlagError::NotFound) => Err(FlagError::NotFound),
                 _ => Err(FlagError::TypeError),
             })
@@ -343,6 +688,67 @@ impl Context {
             .collect::<Vec<_>>()
     }
 
+    /// Get positional argument at the index converted by `FromStr`
+    /// `FlagError::NotFound` if there are fewer arguments, `FlagError::ValueTypeError` if
+    /// the conversion fails
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::Context;
+    ///
+    /// fn action(c: &Context) {
+    ///     match c.arg::<usize>(0) {
+    ///         Ok(n) => println!("{}", n),
+    ///         Err(e) => println!("{}", e)
+    ///     }
+    /// }
+    /// ```
+    pub fn arg<T: FromStr>(&self, index: usize) -> Result<T, FlagError> {
+        match self.args.get(index) {
+            Some(arg) => arg.parse().map_err(|_| FlagError::ValueTypeError),
+            None => Err(FlagError::NotFound),
+        }
+    }
+
+    /// Convert the flags and arguments into a typed struct in one call,
+    /// collecting the errors of all of them
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::error::ContextError;
+    /// use seahorse::{Context, FromContext};
+    ///
+    /// struct CopyArgs {
+    ///     force: bool,
+    ///     source: String,
+    /// }
+    ///
+    /// impl FromContext for CopyArgs {
+    ///     fn from_context(c: &Context) -> Result<Self, ContextError> {
+    ///         let mut errors = ContextError::default();
+    ///         let source = errors.check("<SOURCE>", c.arg(0));
+    ///         errors.into_result()?;
+    ///
+    ///         Ok(Self {
+    ///             force: c.bool_flag("force"),
+    ///             source: source.unwrap(),
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// fn action(c: &Context) {
+    ///     match c.parse_into::<CopyArgs>() {
+    ///         Ok(args) => println!("{} {}", args.force, args.source),
+    ///         Err(e) => println!("{}", e)
+    ///     }
+    /// }
+    /// ```
+    pub fn parse_into<T: FromContext>(&self) -> Result<T, ContextError> {
+        T::from_context(self)
+    }
+
     /// Display help
     ///
     /// Example
@@ -359,11 +765,89 @@ impl Context {
     }
 }
 
+/// Check that deprecated flags and the flags replacing them are not specified together in `args`,
+/// as one of the values would be dropped
+pub(crate) fn validate_replaced_flags(
+    flags: &Option<Vec<Flag>>,
+    args: &[String],
+) -> Result<(), ActionError> {
+    for flag in flags.iter().flatten() {
+        let replacement = match &flag.replaced_by {
+            Some(name) if name != &flag.name => name,
+            _ => continue,
+        };
+        let replacement_used = flags
+            .iter()
+            .flatten()
+            .any(|f| &f.name == replacement && f.option_index(args).is_some());
+        if replacement_used && flag.option_index(args).is_some() {
+            return Err(ActionError {
+                kind: ActionErrorKind::ConflictingFlags(flag.name.clone(), replacement.clone()),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Get default value of the flag, or `FlagError::NotFound` if it has none
+fn default_flag_value(flag: &Flag) -> Result<FlagValue, FlagError> {
+    match &flag.default_value {
+        Some(default_value) if flag.flag_type != FlagType::Bool => {
+            flag.value(Some(default_value.clone()))
+        }
+        _ => Err(FlagError::NotFound),
+    }
+}
+
+/// Convert value from the environment variable or config file
+/// Bool flags accept "true", "1", "yes" and "on", and are not set by "false", "0", "no" and "off"
+fn fallback_flag_value(flag: &Flag, value: String) -> Option<Result<FlagValue, FlagError>> {
+    match flag.flag_type {
+        FlagType::Bool => match value.to_lowercase().as_str() {
+            "true" | "1" | "yes" | "on" => Some(Ok(FlagValue::Bool(true))),
+            "false" | "0" | "no" | "off" | "" => None,
+            _ => Some(Err(FlagError::ValueTypeError)),
+        },
+        _ => Some(flag.value(Some(value))),
+    }
+}
+
+/// Check whether the value of `flag` equals `value` of a conditional default,
+/// compared as parsed values so that "1" matches "1.0" of float flags
+/// Bool flags match "false" when they are not set
+fn condition_matches(flag: &Flag, value: &str, current: &Result<FlagValue, FlagError>) -> bool {
+    if flag.flag_type == FlagType::Bool {
+        let set = matches!(current, Ok(FlagValue::Bool(true)));
+        return match fallback_flag_value(flag, value.to_string()) {
+            Some(Ok(_)) => set,
+            Some(Err(_)) => false,
+            None => !set,
+        };
+    }
+
+    match (flag.value(Some(value.to_string())), current) {
+        (Ok(expected), Ok(current)) => &expected == current,
+        _ => false,
+    }
+}
+
+/// Get flag value as it is written on the command line
+fn value_text(value: &FlagValue) -> String {
+    match value {
+        FlagValue::Bool(b) => b.to_string(),
+        FlagValue::String(s) => s.clone(),
+        FlagValue::Int(i) => i.to_string(),
+        FlagValue::Uint(u) => u.to_string(),
+        FlagValue::Float(f) => f.to_string(),
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
-    use crate::error::FlagError;
+    use crate::error::{ActionErrorKind, ContextError, FlagError};
     use crate::utils::normalized_args;
-    use crate::{Context, Flag, FlagType};
+    use crate::{Context, Flag, FlagType, FromContext, ValueSource};
 
     #[test]
     fn context_test() {
@@ -430,4 +914,214 @@ mod tests {
             Err(FlagError::NotFound)
         );
     }
+
+    #[test]
+    fn replaced_by_test() {
+        let args = vec!["cli".to_string(), "-o".to_string(), "out.txt".to_string()];
+        let flags = vec![
+            Flag::new("output", FlagType::String),
+            Flag::new("out", FlagType::String)
+                .alias("o")
+                .deprecated("use --output")
+                .replaced_by("output"),
+        ];
+        let context = Context::new(normalized_args(args), Some(flags.clone()), "".to_string());
+
+        assert_eq!(context.string_flag("output"), Ok("out.txt".to_string()));
+        assert_eq!(context.string_flag("out"), Err(FlagError::NotFound));
+        assert_eq!(context.args, vec!["cli".to_string()]);
+
+        let args = ["cli", "-o", "out.txt", "--output", "output.txt"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            super::validate_replaced_flags(&Some(flags), &args)
+                .unwrap_err()
+                .kind,
+            ActionErrorKind::ConflictingFlags("out".to_string(), "output".to_string())
+        );
+        let args = vec!["cli".to_string(), "--old".to_string(), "x".to_string()];
+        let flags = vec![Flag::new("old", FlagType::String)
+            .deprecated("use --new")
+            .replaced_by("new")];
+        let context = Context::new(args, Some(flags), "".to_string());
+
+        assert_eq!(context.string_flag("old"), Ok("x".to_string()));
+        assert_eq!(context.args, vec!["cli".to_string()]);
+    }
+
+    #[test]
+    fn default_value_if_test() {
+        let flags = vec![
+            Flag::new("tls", FlagType::Bool),
+            Flag::new("port", FlagType::Int)
+                .default_value("80")
+                .default_value_if("tls", "true", "443"),
+        ];
+
+        let args = vec!["cli".to_string()];
+        let context = Context::new(args, Some(flags.clone()), "".to_string());
+        assert_eq!(context.int_flag("port"), Ok(80));
+
+        let args = vec!["cli".to_string(), "--tls".to_string()];
+        let context = Context::new(args, Some(flags.clone()), "".to_string());
+        assert_eq!(context.int_flag("port"), Ok(443));
+
+        let args = vec![
+            "cli".to_string(),
+            "--tls".to_string(),
+            "--port".to_string(),
+            "8443".to_string(),
+        ];
+        let context = Context::new(args, Some(flags), "".to_string());
+        assert_eq!(context.int_flag("port"), Ok(8443));
+
+        let flags = vec![
+            Flag::new("cache", FlagType::Bool),
+            Flag::new("ratio", FlagType::Float),
+            Flag::new("mode", FlagType::String)
+                .default_value("default")
+                .default_value_if("cache", "false", "no-cache")
+                .default_value_if("ratio", "1", "full"),
+        ];
+
+        let args = vec!["cli".to_string()];
+        let context = Context::new(args, Some(flags.clone()), "".to_string());
+        assert_eq!(context.string_flag("mode"), Ok("no-cache".to_string()));
+
+        let args = vec![
+            "cli".to_string(),
+            "--cache".to_string(),
+            "--ratio".to_string(),
+            "1.0".to_string(),
+        ];
+        let context = Context::new(args, Some(flags.clone()), "".to_string());
+        assert_eq!(context.string_flag("mode"), Ok("full".to_string()));
+
+        let args = vec![
+            "cli".to_string(),
+            "--cache".to_string(),
+            "--ratio".to_string(),
+            "0.5".to_string(),
+        ];
+        let context = Context::new(args, Some(flags), "".to_string());
+        assert_eq!(context.string_flag("mode"), Ok("default".to_string()));
+    }
+
+    #[test]
+    fn value_source_test() {
+        let flags = vec![
+            Flag::new("host", FlagType::String),
+            Flag::new("port", FlagType::Int).default_value("80"),
+            Flag::new("token", FlagType::String),
+            Flag::new("user", FlagType::String),
+            Flag::new("verbose", FlagType::Bool),
+        ];
+        let args = vec!["cli".to_string(), "--host".to_string(), "a.com".to_string()];
+        let context = Context::parse(args, Some(flags), "".to_string(), |f| match &*f.name {
+            "token" => Some((
+                "secret".to_string(),
+                ValueSource::Environment("CLI_TOKEN".to_string()),
+            )),
+            "user" => Some((
+                "john".to_string(),
+                ValueSource::ConfigFile("config".to_string(), 2),
+            )),
+            _ => None,
+        });
+
+        assert_eq!(context.value_source("host"), Some(ValueSource::CommandLine));
+        assert_eq!(context.value_source("port"), Some(ValueSource::Default));
+        assert_eq!(
+            context.value_source("token"),
+            Some(ValueSource::Environment("CLI_TOKEN".to_string()))
+        );
+        assert_eq!(
+            context.value_source("user"),
+            Some(ValueSource::ConfigFile("config".to_string(), 2))
+        );
+        assert_eq!(context.value_source("undefined"), None);
+        assert_eq!(
+            context.value_sources_text(),
+            "host    = a.com [command line]\n\
+             port    = 80 [default]\n\
+             token   = secret [env CLI_TOKEN]\n\
+             user    = john [config config:2]\n\
+             verbose = (not set) [default]\n"
+        );
+    }
+
+    #[test]
+    fn parse_into_test() {
+        #[derive(Debug, PartialEq)]
+        struct ServeArgs {
+            port: usize,
+            hosts: Vec<String>,
+            timeout: Option<f64>,
+            root: String,
+            workers: usize,
+        }
+
+        impl FromContext for ServeArgs {
+            fn from_context(c: &Context) -> Result<Self, ContextError> {
+                let mut errors = ContextError::default();
+                let port = errors.check("--port", c.uint_flag("port"));
+                let hosts = errors.check_vec("--host", c.string_flag_vec("host"));
+                let timeout = errors.check_optional("--timeout", c.float_flag("timeout"));
+                let root = errors.check("<ROOT>", c.arg(0));
+                let workers = errors.check("<WORKERS>", c.arg(1));
+                errors.into_result()?;
+
+                Ok(Self {
+                    port: port.unwrap(),
+                    hosts: hosts.unwrap(),
+                    timeout: timeout.unwrap(),
+                    root: root.unwrap(),
+                    workers: workers.unwrap(),
+                })
+            }
+        }
+
+        let flags = || {
+            Some(vec![
+                Flag::new("port", FlagType::Uint),
+                Flag::new("host", FlagType::String).multiple(),
+                Flag::new("timeout", FlagType::Float),
+            ])
+        };
+        let args = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        let context = Context::new(
+            args(&["--port", "80", "--host", "a", "--host", "b", "/srv", "4"]),
+            flags(),
+            String::new(),
+        );
+        assert_eq!(
+            context.parse_into::<ServeArgs>(),
+            Ok(ServeArgs {
+                port: 80,
+                hosts: vec!["a".to_string(), "b".to_string()],
+                timeout: None,
+                root: "/srv".to_string(),
+                workers: 4,
+            })
+        );
+
+        let context = Context::new(
+            args(&["--timeout", "x", "/srv", "many"]),
+            flags(),
+            String::new(),
+        );
+        assert_eq!(
+            context.parse_into::<ServeArgs>(),
+            Err(ContextError {
+                errors: vec![
+                    ("--port".to_string(), FlagError::NotFound),
+                    ("--timeout".to_string(), FlagError::ValueTypeError),
+                    ("<WORKERS>".to_string(), FlagError::ValueTypeError),
+                ]
+            })
+        );
+    }
 }
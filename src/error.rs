@@ -15,47 +15,248 @@ impl fmt::Display for ActionError {
 impl std::error::Error for ActionError {}
 
 #[derive(PartialEq, Clone, Debug)]
+#[non_exhaustive]
 pub enum ActionErrorKind {
     NotFound,
+    /// Required flag is not specified
+    MissingRequiredFlag(String),
+    /// Required positional argument is not specified
+    MissingRequiredArg(String),
+    /// Flags that cannot be used together are specified
+    ConflictingFlags(String, String),
+    /// None of the flags and positional arguments in a required group is specified
+    MissingRequiredGroup(Vec<String>, Vec<String>),
+    /// Flag is specified with a positional argument it conflicts with
+    ConflictingArg(String, String),
+    /// Positional arguments that cannot be used together are specified
+    ConflictingArgs(String, String),
+    /// Flag is specified without a flag it requires
+    MissingRequiredBy(String, String),
+    /// Flag is not specified and none of the flags that make it optional is specified
+    MissingRequiredUnless(String, Vec<String>),
+    /// Shell passed to the built-in `completions` command is not supported
+    UnknownShell(String),
 }
 
 impl fmt::Display for ActionErrorKind {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match *self {
+        match self {
             ActionErrorKind::NotFound => f.write_str("NotFound"),
+            ActionErrorKind::MissingRequiredFlag(name) => {
+                write!(f, "Required flag \"--{}\" is not specified", name)
+            }
+            ActionErrorKind::MissingRequiredArg(name) => {
+                write!(f, "Required argument <{}> is not specified", name)
+            }
+            ActionErrorKind::ConflictingFlags(a, b) => {
+                write!(
+                    f,
+                    "Flags \"--{}\" and \"--{}\" cannot be used together",
+                    a, b
+                )
+            }
+            ActionErrorKind::MissingRequiredGroup(flags, args) => {
+                let flags = flags
+                    .iter()
+                    .map(|name| format!("\"--{}\"", name))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let args = args
+                    .iter()
+                    .map(|name| format!("<{}>", name))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                match (flags.is_empty(), args.is_empty()) {
+                    (_, true) => write!(f, "One of the flags {} must be specified", flags),
+                    (true, false) => write!(f, "One of the arguments {} must be specified", args),
+                    (false, false) => write!(
+                        f,
+                        "One of the flags {} or arguments {} must be specified",
+                        flags, args
+                    ),
+                }
+            }
+            ActionErrorKind::ConflictingArg(flag, arg) => {
+                write!(
+                    f,
+                    "Flag \"--{}\" cannot be used with argument <{}>",
+                    flag, arg
+                )
+            }
+            ActionErrorKind::ConflictingArgs(a, b) => {
+                write!(f, "Arguments <{}> and <{}> cannot be used together", a, b)
+            }
+            ActionErrorKind::MissingRequiredBy(flag, required) => {
+                write!(f, "Flag \"--{}\" requires \"--{}\"", flag, required)
+            }
+            ActionErrorKind::MissingRequiredUnless(flag, unless) => {
+                let unless = unless
+                    .iter()
+                    .map(|name| format!("\"--{}\"", name))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(
+                    f,
+                    "Required flag \"--{}\" is not specified, unless {} is used",
+                    flag, unless
+                )
+            }
+            ActionErrorKind::UnknownShell(name) => {
+                write!(f, "Unknown shell \"{}\", expected bash, zsh or fish", name)
+            }
         }
     }
 }
 
 #[derive(PartialEq, Clone, Debug)]
+#[non_exhaustive]
 pub enum FlagError {
     NotFound,
     Undefined,
     TypeError,
     ValueTypeError,
     ArgumentError,
+    /// Value is not one of the possible values of the flag, which are listed
+    UnexpectedValue(Vec<String>),
 }
 
 impl fmt::Display for FlagError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match *self {
+        match self {
             FlagError::NotFound => f.write_str("NotFound"),
             FlagError::Undefined => f.write_str("Undefined"),
             FlagError::TypeError => f.write_str("TypeError"),
             FlagError::ValueTypeError => f.write_str("ValueTypeError"),
             FlagError::ArgumentError => f.write_str("ArgumentError"),
+            FlagError::UnexpectedValue(values) => {
+                write!(
+                    f,
+                    "UnexpectedValue (possible values: {})",
+                    values.join(", ")
+                )
+            }
         }
     }
 }
 
 impl error::Error for FlagError {
     fn description(&self) -> &str {
-        match *self {
+        match self {
             FlagError::NotFound => "Flag not found",
             FlagError::Undefined => "Flag undefined",
             FlagError::TypeError => "Flag type mismatch",
             FlagError::ValueTypeError => "Value type mismatch",
             FlagError::ArgumentError => "Illegal argument",
+            FlagError::UnexpectedValue(_) => "Value is not one of the possible values",
         }
     }
 }
+
+/// Error of parsing the spec passed to `App::from_spec`
+#[derive(PartialEq, Clone, Debug)]
+pub enum SpecError {
+    /// Text is not valid JSON, with the line and column starting from 1
+    Syntax {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// Field is missing or has an invalid value, with the path of the field like `commands[0].name`
+    InvalidField { field: String, expected: String },
+}
+
+impl fmt::Display for SpecError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SpecError::Syntax {
+                line,
+                column,
+                message,
+            } => write!(f, "Invalid JSON at {}:{}: {}", line, column, message),
+            SpecError::InvalidField { field, expected } => {
+                write!(f, "Invalid field \"{}\": expected {}", field, expected)
+            }
+        }
+    }
+}
+
+impl error::Error for SpecError {}
+
+/// Errors of all the flags and arguments that could not be converted by `FromContext`
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct ContextError {
+    /// Name of the flag like `--port` or the argument like `<FILE>` with its error
+    pub errors: Vec<(String, FlagError)>,
+}
+
+impl ContextError {
+    /// Get the value, or record the error of the flag or argument and get `None`
+    pub fn check<T>(&mut self, name: &str, result: Result<T, FlagError>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.errors.push((name.to_string(), e));
+                None
+            }
+        }
+    }
+
+    /// Same as `check`, but a value that is not specified is `Some(None)` instead of an error
+    pub fn check_optional<T>(
+        &mut self,
+        name: &str,
+        result: Result<T, FlagError>,
+    ) -> Option<Option<T>> {
+        match result {
+            Err(FlagError::NotFound) => Some(None),
+            result => self.check(name, result).map(Some),
+        }
+    }
+
+    /// Get values of the repeated flag, or record the first error and get `None`
+    pub fn check_vec<T>(
+        &mut self,
+        name: &str,
+        results: Vec<Result<T, FlagError>>,
+    ) -> Option<Vec<T>> {
+        let result = results
+            .into_iter()
+            .filter(|r| !matches!(r, Err(FlagError::NotFound)))
+            .collect();
+        self.check(name, result)
+    }
+
+    /// Get `Ok` if no error is recorded
+    pub fn into_result(self) -> Result<(), Self> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl fmt::Display for ContextError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let lines = self
+            .errors
+            .iter()
+            .map(|(name, e)| {
+                let reason = match e {
+                    FlagError::NotFound => "not specified".to_string(),
+                    FlagError::Undefined => "not defined".to_string(),
+                    FlagError::TypeError => "unexpected type".to_string(),
+                    FlagError::ValueTypeError => "invalid value".to_string(),
+                    FlagError::ArgumentError => "invalid argument".to_string(),
+                    FlagError::UnexpectedValue(values) => {
+                        format!("expected one of {}", values.join(", "))
+                    }
+                };
+                format!("{}: {}", name, reason)
+            })
+            .collect::<Vec<_>>();
+        f.write_str(&lines.join("\n"))
+    }
+}
+
+impl error::Error for ContextError {}
@@ -1,4 +1,5 @@
 use crate::error::FlagError;
+use crate::Completer;
 
 /// `Flag` type.
 ///
@@ -9,12 +10,47 @@ pub struct Flag {
     pub name: String,
     /// Flag description
     pub description: Option<String>,
+    /// Flag description shown in long help
+    pub long_description: Option<String>,
     /// Flag type
     pub flag_type: FlagType,
     /// Flag alias
     pub alias: Option<Vec<String>>,
     /// Multiple occurrence
     pub multiple: bool,
+    /// Required flag
+    pub required: bool,
+    /// Inherited by sub commands
+    pub global: bool,
+    /// Value used when the flag is not specified
+    pub default_value: Option<String>,
+    /// Values used when the flag is not specified and another flag has a given value,
+    /// as tuples of the other flag name, its value and the default value
+    pub default_value_if: Option<Vec<(String, String, String)>>,
+    /// Environment variable read when the flag is not specified
+    pub env: Option<String>,
+    /// Not read from the environment variable derived from `App::env_prefix`
+    pub no_env_prefix: bool,
+    /// Values accepted by the flag
+    pub possible_values: Option<Vec<String>>,
+    /// Source of dynamic completion candidates for the value
+    pub completer: Option<Completer>,
+    /// Hidden from help
+    pub hidden: bool,
+    /// Deprecation note
+    pub deprecated: Option<String>,
+    /// Name of the flag that receives values of this deprecated flag
+    pub replaced_by: Option<String>,
+    /// Heading to group the flag under in help
+    pub help_heading: Option<String>,
+    /// Name of the value placeholder in help
+    pub value_name: Option<String>,
+    /// Names of the flags that must be specified with this flag
+    pub requires: Option<Vec<String>>,
+    /// Names of the flags or positional arguments that cannot be used with this flag
+    pub conflicts_with: Option<Vec<String>>,
+    /// Names of the flags that make this flag optional
+    pub required_unless: Option<Vec<String>>,
 }
 
 /// `FlagType` enum
@@ -27,6 +63,31 @@ pub enum FlagType {
     Float,
 }
 
+impl FlagType {
+    /// Get type from the name used in docs and the spec
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "bool" => Some(FlagType::Bool),
+            "string" => Some(FlagType::String),
+            "int" => Some(FlagType::Int),
+            "uint" => Some(FlagType::Uint),
+            "float" => Some(FlagType::Float),
+            _ => None,
+        }
+    }
+
+    /// Get name of the type used in docs and the spec, e.g. `uint`
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            FlagType::Bool => "bool",
+            FlagType::String => "string",
+            FlagType::Int => "int",
+            FlagType::Uint => "uint",
+            FlagType::Float => "float",
+        }
+    }
+}
+
 /// `FlagValue` enum
 #[derive(PartialEq, Clone, Debug)]
 pub enum FlagValue {
@@ -72,9 +133,26 @@ impl Flag {
         Self {
             name,
             description: None,
+            long_description: None,
             flag_type,
             alias: None,
             multiple: false,
+            required: false,
+            global: false,
+            default_value: None,
+            default_value_if: None,
+            env: None,
+            no_env_prefix: false,
+            possible_values: None,
+            completer: None,
+            hidden: false,
+            deprecated: None,
+            replaced_by: None,
+            help_heading: None,
+            value_name: None,
+            requires: None,
+            conflicts_with: None,
+            required_unless: None,
         }
     }
 
@@ -93,6 +171,146 @@ impl Flag {
         self
     }
 
+    /// Set long description of the flag shown in `--help`
+    /// `-h` shows the description set by `Flag::description`
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let int_flag = Flag::new("port", FlagType::Int)
+    ///     .description("Port number")
+    ///     .long_description("Port number to listen on. Ports below 1024 require root privileges.");
+    /// ```
+    pub fn long_description<T: Into<String>>(mut self, description: T) -> Self {
+        self.long_description = Some(description.into());
+        self
+    }
+
+    /// Set default value of the flag used when the flag is not specified
+    /// Default value is not used for bool flags
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let int_flag = Flag::new("port", FlagType::Int)
+    ///     .default_value("80");
+    /// ```
+    pub fn default_value<T: Into<String>>(mut self, value: T) -> Self {
+        self.default_value = Some(value.into());
+        self
+    }
+
+    /// Set default value of the flag used when the flag is not specified
+    /// and the other flag has the given value, compared after parsing it as the type of the other flag
+    /// Bool flags are compared with "true" or "false"
+    /// Conditional default value takes precedence over `default_value`
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let int_flag = Flag::new("port", FlagType::Int)
+    ///     .default_value("80")
+    ///     .default_value_if("tls", "true", "443");
+    /// ```
+    pub fn default_value_if<T: Into<String>, U: Into<String>, V: Into<String>>(
+        mut self,
+        other_flag: T,
+        value: U,
+        default: V,
+    ) -> Self {
+        let condition = (other_flag.into(), value.into(), default.into());
+        if let Some(ref mut default_value_if) = self.default_value_if {
+            (*default_value_if).push(condition);
+        } else {
+            self.default_value_if = Some(vec![condition]);
+        }
+        self
+    }
+
+    /// Set environment variable read when the flag is not specified
+    /// Environment variable takes precedence over the config file and default value
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let string_flag = Flag::new("token", FlagType::String)
+    ///     .env("MYTOOL_TOKEN");
+    /// ```
+    pub fn env<T: Into<String>>(mut self, var: T) -> Self {
+        self.env = Some(var.into());
+        self
+    }
+
+    /// Opt out of the environment variable derived from `App::env_prefix`
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let bool_flag = Flag::new("dry-run", FlagType::Bool)
+    ///     .no_env_prefix();
+    /// ```
+    pub fn no_env_prefix(mut self) -> Self {
+        self.no_env_prefix = true;
+        self
+    }
+
+    /// Set values accepted by the flag, which are completed automatically
+    /// Other values result in `FlagError::UnexpectedValue` listing the possible values
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let string_flag = Flag::new("format", FlagType::String)
+    ///     .possible_values(["json", "yaml", "table"]);
+    /// ```
+    pub fn possible_values<I, T>(mut self, values: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.possible_values = Some(values.into_iter().map(|v| v.into()).collect());
+        self
+    }
+
+    /// Set source of dynamic completion candidates for the value
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Completer, Flag, FlagType};
+    ///
+    /// let string_flag = Flag::new("config", FlagType::String)
+    ///     .completer(Completer::Files);
+    ///
+    /// let string_flag = Flag::new("branch", FlagType::String)
+    ///     .completer(Completer::Custom(|_| vec!["main".to_string(), "develop".to_string()]));
+    /// ```
+    pub fn completer(mut self, completer: Completer) -> Self {
+        self.completer = Some(completer);
+        self
+    }
+
+    /// Get source of completion candidates, falling back to the possible values
+    pub(crate) fn value_completer(&self) -> Option<Completer> {
+        self.completer.clone().or_else(|| {
+            self.possible_values
+                .as_ref()
+                .map(|values| Completer::Values(values.clone()))
+        })
+    }
+
     /// Set multiple flag
     ///
     /// Example
@@ -108,6 +326,122 @@ impl Flag {
         self
     }
 
+    /// Set required flag, shown in the usage line and checked before running the action
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let string_flag = Flag::new("string", FlagType::String)
+    ///     .required();
+    /// ```
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Set global flag, which is also accepted by all sub commands
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Flag, FlagType};
+    ///
+    /// let app = App::new("cli")
+    ///     .flag(Flag::new("verbose", FlagType::Bool).global());
+    /// ```
+    pub fn global(mut self) -> Self {
+        self.global = true;
+        self
+    }
+
+    /// Set hidden flag, which is parsed as usual but not shown in help
+    /// Hidden flags are shown by `--help-all`
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let bool_flag = Flag::new("debug", FlagType::Bool)
+    ///     .hidden();
+    /// ```
+    pub fn hidden(mut self) -> Self {
+        self.hidden = true;
+        self
+    }
+
+    /// Set deprecated flag, which is parsed as usual but prints a warning when used
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let string_flag = Flag::new("out", FlagType::String)
+    ///     .deprecated("use --output");
+    /// ```
+    pub fn deprecated<T: Into<String>>(mut self, note: T) -> Self {
+        self.deprecated = Some(note.into());
+        self
+    }
+
+    /// Forward values of the flag to the flag named `name`,
+    /// so that actions only read the replacement flag
+    /// Specifying both flags is an error, as one of the values would be dropped
+    /// The flag keeps its own value if no flag named `name` is defined
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{App, Flag, FlagType};
+    ///
+    /// let app = App::new("cli")
+    ///     .flag(Flag::new("output", FlagType::String))
+    ///     .flag(
+    ///         Flag::new("out", FlagType::String)
+    ///             .deprecated("use --output")
+    ///             .replaced_by("output"),
+    ///     );
+    /// ```
+    pub fn replaced_by<T: Into<String>>(mut self, name: T) -> Self {
+        self.replaced_by = Some(name.into());
+        self
+    }
+
+    /// Set name of the value placeholder shown in help
+    /// e.g. `--config <FILE>`
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let string_flag = Flag::new("config", FlagType::String)
+    ///     .value_name("FILE");
+    /// ```
+    pub fn value_name<T: Into<String>>(mut self, name: T) -> Self {
+        self.value_name = Some(name.into());
+        self
+    }
+
+    /// Set heading to group the flag under in help
+    /// Flags without heading are shown under "Flags"
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let int_flag = Flag::new("port", FlagType::Int)
+    ///     .help_heading("Network");
+    /// ```
+    pub fn help_heading<T: Into<String>>(mut self, heading: T) -> Self {
+        self.help_heading = Some(heading.into());
+        self
+    }
+
     /// Set alias of the flag
     ///
     /// Example
@@ -131,6 +465,65 @@ impl Flag {
         self
     }
 
+    /// Set flag that must be specified with this flag
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let string_flag = Flag::new("key", FlagType::String)
+    ///     .requires("cert");
+    /// ```
+    pub fn requires<T: Into<String>>(mut self, name: T) -> Self {
+        if let Some(ref mut requires) = self.requires {
+            (*requires).push(name.into());
+        } else {
+            self.requires = Some(vec![name.into()]);
+        }
+        self
+    }
+
+    /// Set flag or positional argument that cannot be used with this flag
+    /// A positional argument conflicts only when a value is passed in its position
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let bool_flag = Flag::new("stdin", FlagType::Bool)
+    ///     .conflicts_with("FILE");
+    /// ```
+    pub fn conflicts_with<T: Into<String>>(mut self, name: T) -> Self {
+        if let Some(ref mut conflicts_with) = self.conflicts_with {
+            (*conflicts_with).push(name.into());
+        } else {
+            self.conflicts_with = Some(vec![name.into()]);
+        }
+        self
+    }
+
+    /// Set flag that makes this flag optional, otherwise this flag is required
+    /// A default value of the flag also satisfies the requirement
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::{Flag, FlagType};
+    ///
+    /// let string_flag = Flag::new("token", FlagType::String)
+    ///     .required_unless("anonymous");
+    /// ```
+    pub fn required_unless<T: Into<String>>(mut self, name: T) -> Self {
+        if let Some(ref mut required_unless) = self.required_unless {
+            (*required_unless).push(name.into());
+        } else {
+            self.required_unless = Some(vec![name.into()]);
+        }
+        self
+    }
+
     /// Get flag position from command line argument
     pub fn option_index(&self, v: &[String]) -> Option<usize> {
         match &self.alias {
@@ -148,8 +541,37 @@ impl Flag {
         }
     }
 
+    /// Get flag value placeholder for help and usage
+    /// e.g. "<FILE>", "<int>" or "<string>..." for multiple flags
+    pub(crate) fn value_placeholder(&self) -> Option<String> {
+        let name = match (&self.value_name, &self.flag_type) {
+            (_, FlagType::Bool) => return None,
+            (Some(value_name), _) => value_name.as_str(),
+            (None, FlagType::String) => "string",
+            (None, FlagType::Int) => "int",
+            (None, FlagType::Uint) => "uint",
+            (None, FlagType::Float) => "float",
+        };
+        let dots = if self.multiple { "..." } else { "" };
+        Some(format!("<{}>{}", name, dots))
+    }
+
+    /// Get flag notation for usage
+    pub(crate) fn usage_text(&self) -> String {
+        match self.value_placeholder() {
+            Some(val) => format!("--{} {}", self.name, val),
+            None => format!("--{}", self.name),
+        }
+    }
+
     /// Get flag value
     pub fn value(&self, v: Option<String>) -> Result<FlagValue, FlagError> {
+        if let (Some(values), Some(v)) = (&self.possible_values, &v) {
+            if !values.contains(v) {
+                return Err(FlagError::UnexpectedValue(values.clone()));
+            }
+        }
+
         match self.flag_type {
             FlagType::Bool => Ok(FlagValue::Bool(true)),
             FlagType::String => match v {
@@ -182,7 +604,9 @@ impl Flag {
 }
 
 #[cfg(test)]
+#[allow(clippy::assertions_on_constants, clippy::useless_vec)]
 mod tests {
+    use crate::error::{ContextError, FlagError};
     use crate::{Flag, FlagType, FlagValue};
 
     #[test]
@@ -209,6 +633,49 @@ mod tests {
         }
     }
 
+    #[test]
+    fn value_placeholder_test() {
+        assert_eq!(Flag::new("bool", FlagType::Bool).value_placeholder(), None);
+        assert_eq!(
+            Flag::new("uint", FlagType::Uint).value_placeholder(),
+            Some("<uint>".to_string())
+        );
+        assert_eq!(
+            Flag::new("config", FlagType::String)
+                .value_name("FILE")
+                .usage_text(),
+            "--config <FILE>"
+        );
+        assert_eq!(
+            Flag::new("include", FlagType::String)
+                .value_name("FILE")
+                .multiple()
+                .usage_text(),
+            "--include <FILE>..."
+        );
+    }
+
+    #[test]
+    fn possible_values_test() {
+        let f = Flag::new("format", FlagType::String).possible_values(["json", "yaml"]);
+        assert_eq!(
+            f.value(Some("json".to_string())),
+            Ok(FlagValue::String("json".to_string()))
+        );
+        let error = f.value(Some("xml".to_string())).unwrap_err();
+        assert_eq!(
+            error,
+            FlagError::UnexpectedValue(vec!["json".to_string(), "yaml".to_string()])
+        );
+        assert_eq!(
+            ContextError {
+                errors: vec![("--format".to_string(), error)]
+            }
+            .to_string(),
+            "--format: expected one of json, yaml"
+        );
+    }
+
     #[test]
     #[should_panic]
     fn construct_fail_1() {
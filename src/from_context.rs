@@ -0,0 +1,37 @@
+use crate::error::ContextError;
+use crate::Context;
+
+/// `FromContext` trait
+///
+/// Convert the flags and arguments of the context into a typed struct,
+/// collecting the errors of all of them instead of stopping at the first one
+///
+/// Example
+///
+/// ```
+/// use seahorse::error::ContextError;
+/// use seahorse::{Context, FromContext};
+///
+/// struct DeployArgs {
+///     env: String,
+///     port: Option<usize>,
+/// }
+///
+/// impl FromContext for DeployArgs {
+///     fn from_context(c: &Context) -> Result<Self, ContextError> {
+///         let mut errors = ContextError::default();
+///         let env = errors.check("--env", c.string_flag("env"));
+///         let port = errors.check_optional("--port", c.uint_flag("port"));
+///         errors.into_result()?;
+///
+///         Ok(Self {
+///             env: env.unwrap(),
+///             port: port.unwrap(),
+///         })
+///     }
+/// }
+/// ```
+pub trait FromContext: Sized {
+    /// Build the value from the context
+    fn from_context(c: &Context) -> Result<Self, ContextError>;
+}
@@ -0,0 +1,212 @@
+use crate::{Arg, Flag};
+
+/// `ArgGroup` type.
+///
+/// Group of flags and positional arguments checked together during parsing
+#[derive(Clone, Debug, Default)]
+pub struct ArgGroup {
+    /// Group name
+    pub name: String,
+    /// Names of the flags in the group
+    pub flags: Vec<String>,
+    /// Names of the positional arguments in the group
+    pub args: Vec<String>,
+    /// At most one member of the group can be specified
+    pub exclusive: bool,
+    /// At least one member of the group must be specified
+    pub required: bool,
+}
+
+impl ArgGroup {
+    /// Create new instance of `ArgGroup`
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::ArgGroup;
+    ///
+    /// let group = ArgGroup::new("format");
+    /// ```
+    pub fn new<T: Into<String>>(name: T) -> Self {
+        Self {
+            name: name.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Add flag to the group
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::ArgGroup;
+    ///
+    /// let group = ArgGroup::new("format")
+    ///     .flag("json")
+    ///     .flag("yaml");
+    /// ```
+    pub fn flag<T: Into<String>>(mut self, name: T) -> Self {
+        self.flags.push(name.into());
+        self
+    }
+
+    /// Add flags to the group
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::ArgGroup;
+    ///
+    /// let group = ArgGroup::new("format")
+    ///     .flags(["json", "yaml", "table"]);
+    /// ```
+    pub fn flags<I, T>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.flags.extend(names.into_iter().map(|name| name.into()));
+        self
+    }
+
+    /// Add positional argument to the group,
+    /// which counts as specified when a value is passed in its position
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::ArgGroup;
+    ///
+    /// let group = ArgGroup::new("target")
+    ///     .flag("all")
+    ///     .arg("NAMES")
+    ///     .exclusive();
+    /// ```
+    pub fn arg<T: Into<String>>(mut self, name: T) -> Self {
+        self.args.push(name.into());
+        self
+    }
+
+    /// Set exclusive group, where members cannot be used together
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::ArgGroup;
+    ///
+    /// let group = ArgGroup::new("format")
+    ///     .flags(["json", "yaml"])
+    ///     .exclusive();
+    /// ```
+    pub fn exclusive(mut self) -> Self {
+        self.exclusive = true;
+        self
+    }
+
+    /// Set required group, where at least one of the members must be specified
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::ArgGroup;
+    ///
+    /// let group = ArgGroup::new("format")
+    ///     .flags(["json", "yaml"])
+    ///     .required();
+    /// ```
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Get group notation for usage
+    /// e.g. "<--json|--yaml>" for required exclusive groups, "[--json|--yaml]" for exclusive ones
+    /// and "<--json|--yaml>..." for required ones accepting several members
+    pub(crate) fn usage_text(
+        &self,
+        flags: &Option<Vec<Flag>>,
+        args: &Option<Vec<Arg>>,
+    ) -> Option<String> {
+        if !self.required && !self.exclusive {
+            return None;
+        }
+
+        let flag_members = self.flags.iter().map(|name| {
+            flags
+                .iter()
+                .flatten()
+                .find(|f| &f.name == name)
+                .map(|f| f.usage_text())
+                .unwrap_or_else(|| format!("--{}", name))
+        });
+        let arg_members =
+            self.args.iter().map(
+                |name| match args.iter().flatten().find(|a| &a.name == name) {
+                    Some(arg) if arg.multiple => format!("{}...", name),
+                    _ => name.clone(),
+                },
+            );
+        let members = flag_members
+            .chain(arg_members)
+            .collect::<Vec<_>>()
+            .join("|");
+
+        match (self.required, self.exclusive) {
+            (true, true) => Some(format!("<{}>", members)),
+            (true, false) => Some(format!("<{}>...", members)),
+            _ => Some(format!("[{}]", members)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Arg, ArgGroup, Flag, FlagType};
+
+    #[test]
+    fn usage_text_test() {
+        let flags = Some(vec![
+            Flag::new("json", FlagType::Bool),
+            Flag::new("yaml", FlagType::Bool),
+            Flag::new("output", FlagType::String),
+        ]);
+
+        assert_eq!(
+            ArgGroup::new("format")
+                .flags(["json", "yaml"])
+                .exclusive()
+                .required()
+                .usage_text(&flags, &None),
+            Some("<--json|--yaml>".to_string())
+        );
+        assert_eq!(
+            ArgGroup::new("out")
+                .flags(["json", "output"])
+                .exclusive()
+                .usage_text(&flags, &None),
+            Some("[--json|--output <string>]".to_string())
+        );
+        assert_eq!(
+            ArgGroup::new("some")
+                .flags(["json", "yaml"])
+                .required()
+                .usage_text(&flags, &None),
+            Some("<--json|--yaml>...".to_string())
+        );
+        assert_eq!(
+            ArgGroup::new("target")
+                .flag("all")
+                .arg("NAMES")
+                .exclusive()
+                .required()
+                .usage_text(&None, &Some(vec![Arg::new("NAMES").multiple()])),
+            Some("<--all|NAMES...>".to_string())
+        );
+        assert_eq!(
+            ArgGroup::new("any")
+                .flags(["json"])
+                .usage_text(&flags, &None),
+            None
+        );
+    }
+}
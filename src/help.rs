@@ -1,7 +1,630 @@
+use crate::color::{paint, Style};
+use crate::utils::{display_width, wrap_text};
+use crate::{Arg, ArgGroup, Command, Flag, Scope};
+use std::env;
+
+/// Columns taken by the tab that indents help entries
+const TAB_WIDTH: usize = 8;
+/// Minimum columns for wrapped descriptions on narrow terminals
+const MIN_WRAP_WIDTH: usize = 20;
+/// Indentation of flag descriptions in long help
+const LONG_HELP_INDENT: usize = 8;
+
 pub(crate) trait Help {
-    fn help_text(&self) -> String;
+    fn help_text(&self, scope: &Scope) -> String;
+
+    fn help(&self, scope: &Scope) {
+        println!("{}", self.help_text(scope));
+    }
+}
+
+/// Build usage from the command path, required flags, positional arguments and sub commands
+/// e.g. "cli remote add [OPTIONS] <NAME> <URL>"
+pub(crate) fn usage_text(
+    path: &str,
+    flags: &Option<Vec<Flag>>,
+    args: &Option<Vec<Arg>>,
+    groups: &Option<Vec<ArgGroup>>,
+    has_commands: bool,
+) -> String {
+    let mut usage = vec![path.to_string(), "[OPTIONS]".to_string()];
+
+    if let Some(flags) = flags {
+        usage.extend(flags.iter().filter(|f| f.required).map(|f| f.usage_text()));
+    }
+
+    if let Some(groups) = groups {
+        usage.extend(groups.iter().filter_map(|g| g.usage_text(flags, args)));
+    }
+
+    // Arguments in groups shown above are not repeated
+    if let Some(args) = args {
+        usage.extend(
+            args.iter()
+                .filter(|a| {
+                    groups
+                        .iter()
+                        .flatten()
+                        .all(|g| (!g.required && !g.exclusive) || !g.args.contains(&a.name))
+                })
+                .map(|a| a.placeholder()),
+        );
+    }
+
+    if has_commands {
+        usage.push("[COMMAND]".to_string());
+    }
+
+    usage.join(" ")
+}
+
+/// Check whether any of the sub commands is shown in this scope
+pub(crate) fn has_visible_commands(commands: &Option<Vec<Command>>, scope: &Scope) -> bool {
+    commands
+        .iter()
+        .flatten()
+        .any(|c| scope.show_hidden || !c.hidden)
+}
+
+/// Get width to wrap help text, falling back to `COLUMNS` environment variable
+pub(crate) fn help_width(width: Option<usize>) -> Option<usize> {
+    width.or_else(|| env::var("COLUMNS").ok().and_then(|c| c.parse().ok()))
+}
+
+/// Rendered sections of help text
+#[derive(Default)]
+pub(crate) struct HelpSections {
+    pub name: String,
+    pub author: Option<String>,
+    pub description: Option<String>,
+    pub usage: String,
+    /// Help texts of flags for each heading, starting with the default "Flags" heading
+    pub flags: Vec<(String, String)>,
+    pub global_flags: Option<String>,
+    /// Help texts of sub commands for each category
+    pub commands: Vec<(String, String)>,
+    pub examples: Option<String>,
+    pub version: Option<String>,
+    pub before_help: Option<String>,
+    pub after_help: Option<String>,
+}
+
+impl HelpSections {
+    /// Render help text with `template`, or with the default layout if not set
+    pub fn render(&self, template: &Option<String>, scope: &Scope) -> String {
+        match template {
+            Some(template) => self.render_template(template, scope),
+            None => self.render_default(scope),
+        }
+    }
+
+    fn render_default(&self, scope: &Scope) -> String {
+        let mut text = String::new();
+
+        if let Some(before_help) = &self.before_help {
+            text += &format!("{}\n\n", before_help);
+        }
+
+        text += &format!("{}\n\t{}\n\n", section("Name", scope), self.name);
+
+        if let Some(author) = &self.author {
+            text += &format!("{}\n\t{}\n\n", section("Author", scope), author);
+        }
+
+        if let Some(description) = &self.description {
+            text += &format!("{}\n\t{}\n\n", section("Description", scope), description);
+        }
+
+        text += &format!("{}\n\t{}\n\n", section("Usage", scope), self.usage);
+
+        text += &self
+            .flags
+            .iter()
+            .map(|(heading, flags)| format!("{}\n{}", section(heading, scope), flags))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if let Some(global_flags) = &self.global_flags {
+            text += &format!("\n{}\n{}", section("Global Flags", scope), global_flags);
+        }
+
+        for (heading, commands) in &self.commands {
+            text += &format!("\n{}\n{}", section(heading, scope), commands);
+        }
+
+        if let Some(examples) = &self.examples {
+            text += &format!("\n{}\n{}", section("Examples", scope), examples);
+        }
+
+        if let Some(version) = &self.version {
+            text += &format!("\n{}\n\t{}\n", section("Version", scope), version);
+        }
+
+        if let Some(after_help) = &self.after_help {
+            text += &format!("\n{}\n", after_help);
+        }
+
+        text
+    }
+
+    /// Replace placeholders like `{name}` in `template` with the sections.
+    /// Unknown placeholders are left as they are.
+    fn render_template(&self, template: &str, scope: &Scope) -> String {
+        let optional = |s: &Option<String>| s.clone().unwrap_or_default();
+        // Groups other than the default one are rendered with their headings
+        let grouped = |groups: &[(String, String)], default_heading: &str| {
+            groups
+                .iter()
+                .map(|(heading, text)| match heading.as_str() {
+                    h if h == default_heading => text.clone(),
+                    h => format!("\n{}\n{}", section(h, scope), text),
+                })
+                .collect::<String>()
+        };
+        let values = [
+            ("name", self.name.clone()),
+            ("author", optional(&self.author)),
+            ("description", optional(&self.description)),
+            ("usage", self.usage.clone()),
+            ("flags", grouped(&self.flags, "Flags")),
+            ("global_flags", optional(&self.global_flags)),
+            ("commands", grouped(&self.commands, "Commands")),
+            ("examples", optional(&self.examples)),
+            ("version", optional(&self.version)),
+            ("before_help", optional(&self.before_help)),
+            ("after_help", optional(&self.after_help)),
+        ];
+
+        let mut text = String::new();
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            text += &rest[..start];
+            rest = &rest[start..];
+
+            let value = rest.find('}').and_then(|end| {
+                values
+                    .iter()
+                    .find(|(key, _)| *key == &rest[1..end])
+                    .map(|(_, value)| (end, value))
+            });
+
+            match value {
+                Some((end, value)) => {
+                    text += value;
+                    rest = &rest[end + 1..];
+                }
+                None => {
+                    text.push('{');
+                    rest = &rest[1..];
+                }
+            }
+        }
+        text += rest;
+
+        text
+    }
+}
+
+/// Split items into the ones without heading and groups of the ones with the same heading,
+/// keeping declaration order
+fn group_by_heading<'a, T>(
+    items: &'a [T],
+    heading: impl Fn(&'a T) -> Option<&'a String>,
+) -> (Vec<&'a T>, Vec<(&'a str, Vec<&'a T>)>) {
+    let mut ungrouped = Vec::new();
+    let mut groups: Vec<(&str, Vec<&T>)> = Vec::new();
+
+    for item in items {
+        match heading(item) {
+            Some(h) => match groups.iter_mut().find(|(g, _)| g == h) {
+                Some((_, group)) => group.push(item),
+                None => groups.push((h, vec![item])),
+            },
+            None => ungrouped.push(item),
+        }
+    }
+
+    (ungrouped, groups)
+}
+
+/// Build help texts of the flags for each help heading
+/// Flags without heading and the help flag come first under the "Flags" heading
+pub(crate) fn grouped_flag_help_text(flags: &[Flag], scope: &Scope) -> Vec<(String, String)> {
+    let (ungrouped, groups) = group_by_heading(flags, |f| f.help_heading.as_ref());
+
+    let mut texts = vec![("Flags".to_string(), flag_help_text(&ungrouped, true, scope))];
+    texts.extend(
+        groups
+            .into_iter()
+            .map(|(heading, flags)| (heading.to_string(), flag_help_text(&flags, false, scope)))
+            .filter(|(_, text)| !text.is_empty()),
+    );
+    texts
+}
+
+/// Build help texts of the sub commands for each category
+/// Commands without category come first under the "Commands" heading
+pub(crate) fn grouped_command_help_text(
+    commands: &[Command],
+    scope: &Scope,
+) -> Vec<(String, String)> {
+    let (ungrouped, groups) = group_by_heading(commands, |c| c.category.as_ref());
+
+    std::iter::once(("Commands", ungrouped))
+        .chain(groups)
+        .filter_map(|(heading, commands)| {
+            command_help_text(&commands, scope).map(|text| (heading.to_string(), text))
+        })
+        .collect()
+}
+
+/// Build help text of the examples
+pub(crate) fn example_help_text(examples: &[String]) -> String {
+    examples.iter().map(|e| format!("\t{}\n", e)).collect()
+}
+
+/// Build section header of help
+pub(crate) fn section(title: &str, scope: &Scope) -> String {
+    paint(&format!("{}:", title), Style::Header, scope.color)
+}
+
+/// Build error message printed to stderr
+pub(crate) fn error_text(message: &str, scope: &Scope) -> String {
+    format!(
+        "{} {}",
+        paint("error:", Style::Error, scope.error_color),
+        message
+    )
+}
+
+/// Build warning message printed to stderr
+pub(crate) fn warning_text(message: &str, scope: &Scope) -> String {
+    format!(
+        "{} {}",
+        paint("warning:", Style::Warning, scope.error_color),
+        message
+    )
+}
+
+/// Print warnings for deprecated flags specified in `args`
+pub(crate) fn warn_deprecated_flags(flags: &Option<Vec<Flag>>, args: &[String], scope: &Scope) {
+    for flag in flags.iter().flatten() {
+        if let Some(note) = &flag.deprecated {
+            if flag.option_index(args).is_some() {
+                let message = format!(r#"flag "--{}" is deprecated: {}"#, flag.name, note);
+                eprintln!("{}", warning_text(&message, scope));
+            }
+        }
+    }
+}
+
+/// Wrap description starting at `indent` columns after the tab,
+/// indenting continuation lines to the same column
+pub(crate) fn wrap_description(description: &str, indent: usize, width: Option<usize>) -> String {
+    let lines = match width {
+        Some(width) => wrap_text(
+            description,
+            width.saturating_sub(TAB_WIDTH + indent).max(MIN_WRAP_WIDTH),
+        ),
+        None => description.lines().map(|l| l.to_string()).collect(),
+    };
+
+    lines.join(&format!("\n\t{}", " ".repeat(indent)))
+}
+
+/// Build help text of the flags, followed by the help flag if `help_flag` is set
+pub(crate) fn flag_help_text(flags: &[&Flag], help_flag: bool, scope: &Scope) -> String {
+    let flags = flags
+        .iter()
+        .copied()
+        .filter(|f| scope.show_hidden || !f.hidden)
+        .collect::<Vec<_>>();
+
+    if scope.long_help {
+        return flag_long_help_text(&flags, help_flag, scope);
+    }
+
+    let mut flag_helps = flags
+        .iter()
+        .map(|f| {
+            let description = match (&f.description, &f.deprecated) {
+                (Some(description), Some(note)) => {
+                    Some(format!("{} (deprecated: {})", description, note))
+                }
+                (None, Some(note)) => Some(format!("(deprecated: {})", note)),
+                (description, None) => description.clone(),
+            };
+            (flag_short_name_text(f), description)
+        })
+        .collect::<Vec<_>>();
+
+    if help_flag {
+        flag_helps.push(("-h, --help".to_string(), Some("Show help".to_string())));
+    }
+
+    let flag_name_max_len = flag_helps
+        .iter()
+        .map(|h| display_width(&h.0))
+        .max()
+        .unwrap_or(0);
+
+    let mut text = String::new();
+    for (name, description) in flag_helps {
+        text += &format!("\t{}", paint(&name, Style::Literal, scope.color));
+
+        if let Some(description) = description {
+            text += &format!(
+                "{} : {}\n",
+                " ".repeat(flag_name_max_len - display_width(&name)),
+                wrap_description(&description, flag_name_max_len + 3, scope.width)
+            );
+        } else {
+            text += "\n";
+        }
+    }
+
+    text
+}
+
+/// Get extended description, defaults and notes of the flag shown in long help
+pub(crate) fn flag_details(f: &Flag) -> Vec<String> {
+    let mut details = Vec::new();
+    if let Some(description) = f.long_description.as_ref().or(f.description.as_ref()) {
+        details.push(description.clone());
+    }
+    if let Some(default_value) = &f.default_value {
+        details.push(format!("[default: {}]", default_value));
+    }
+    for (other, value, default) in f.default_value_if.iter().flatten() {
+        details.push(format!("[default: {} if --{}={}]", default, other, value));
+    }
+    if let Some(values) = &f.possible_values {
+        details.push(format!("[possible values: {}]", values.join(", ")));
+    }
+    if let Some(var) = &f.env {
+        details.push(format!("[env: {}]", var));
+    }
+    if let Some(note) = &f.deprecated {
+        details.push(format!("[deprecated: {}]", note));
+    }
+    details
+}
+
+/// Build long help text of the flags, with extended descriptions and defaults below each flag
+fn flag_long_help_text(flags: &[&Flag], help_flag: bool, scope: &Scope) -> String {
+    let mut flag_helps = flags
+        .iter()
+        .map(|f| (flag_name_text(f), flag_details(f)))
+        .collect::<Vec<_>>();
+
+    if help_flag {
+        flag_helps.push((
+            "-h, --help".to_string(),
+            vec!["Show help (see a summary with '-h')".to_string()],
+        ));
+    }
+
+    flag_helps
+        .iter()
+        .map(|(name, details)| {
+            let mut text = format!("\t{}\n", paint(name, Style::Literal, scope.color));
+            for detail in details {
+                text += &format!(
+                    "\t{}{}\n",
+                    " ".repeat(LONG_HELP_INDENT),
+                    wrap_description(detail, LONG_HELP_INDENT, scope.width)
+                );
+            }
+            text
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Build compact flag notation of short help with aliases and value type
+/// e.g. "-a, --age <int>"
+fn flag_short_name_text(f: &Flag) -> String {
+    let alias = match &f.alias {
+        Some(alias) => alias
+            .iter()
+            .filter(|a| a.len() == 1)
+            .map(|a| format!("-{}", a))
+            .collect::<Vec<String>>()
+            .join(", "),
+        None => String::new(),
+    };
+
+    let long_alias = match &f.alias {
+        Some(alias) => alias
+            .iter()
+            .filter(|a| a.len() > 1)
+            .map(|a| format!("--{}", a))
+            .collect::<Vec<String>>()
+            .join(", "),
+        None => String::new(),
+    };
+
+    let val = f.value_placeholder().unwrap_or_default();
+
+    if alias.is_empty() {
+        if long_alias.is_empty() {
+            format!("--{} {}", f.name, val)
+        } else {
+            format!("{}, --{}, {}", long_alias, f.name, val)
+        }
+    } else if long_alias.is_empty() {
+        format!("{}, --{} {}", alias, f.name, val)
+    } else {
+        format!("{}, {}, --{} {}", alias, long_alias, f.name, val)
+    }
+}
+
+/// Build flag notation of long help with aliases and value type
+/// e.g. "-a, --ag, --age <int>"
+fn flag_name_text(f: &Flag) -> String {
+    let mut names = Vec::new();
+
+    if let Some(alias) = &f.alias {
+        names.extend(
+            alias
+                .iter()
+                .filter(|a| a.len() == 1)
+                .map(|a| format!("-{}", a)),
+        );
+        names.extend(
+            alias
+                .iter()
+                .filter(|a| a.len() > 1)
+                .map(|a| format!("--{}", a)),
+        );
+    }
+
+    names.push(f.usage_text());
+    names.join(", ")
+}
+
+/// Build help text of the sub commands, or `None` if all commands are hidden
+pub(crate) fn command_help_text(commands: &[&Command], scope: &Scope) -> Option<String> {
+    let commands = commands
+        .iter()
+        .copied()
+        .filter(|c| scope.show_hidden || !c.hidden)
+        .collect::<Vec<_>>();
+
+    if commands.is_empty() {
+        return None;
+    }
+
+    let command_names = commands
+        .iter()
+        .map(|c| match &c.alias {
+            Some(alias) => format!("{}, {}", alias.join(", "), c.name),
+            None => c.name.clone(),
+        })
+        .collect::<Vec<_>>();
+
+    let name_max_len = command_names
+        .iter()
+        .map(|n| display_width(n))
+        .max()
+        .unwrap_or(0);
+
+    let mut text = String::new();
+    for (c, command_name) in commands.iter().zip(command_names) {
+        let description = match (&c.description, &c.deprecated) {
+            (Some(description), Some(note)) => format!("{} (deprecated: {})", description, note),
+            (None, Some(note)) => format!("(deprecated: {})", note),
+            (Some(description), None) => description.clone(),
+            (None, None) => String::new(),
+        };
+
+        text += &format!(
+            "\t{} {}: {}\n",
+            paint(&command_name, Style::Literal, scope.color),
+            " ".repeat(name_max_len - display_width(&command_name)),
+            wrap_description(&description, name_max_len + 3, scope.width)
+        );
+    }
+
+    Some(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{flag_help_text, usage_text, HelpSections};
+    use crate::{Arg, Flag, FlagType, Scope};
+
+    #[test]
+    fn usage_text_test() {
+        let flags = Some(vec![
+            Flag::new("bool", FlagType::Bool),
+            Flag::new("token", FlagType::String).required(),
+        ]);
+        let args = Some(vec![
+            Arg::new("NAME").required(),
+            Arg::new("URL").required(),
+        ]);
+
+        assert_eq!(
+            usage_text("cli remote add", &None, &args, &None, false),
+            "cli remote add [OPTIONS] <NAME> <URL>"
+        );
+        assert_eq!(
+            usage_text("cli", &flags, &None, &None, true),
+            "cli [OPTIONS] --token <string> [COMMAND]"
+        );
+    }
+
+    #[test]
+    fn flag_help_text_test() {
+        let flags = [
+            Flag::new("name", FlagType::String).description("名前を指定します"),
+            Flag::new("verbose", FlagType::Bool)
+                .alias("v")
+                .description("Increase verbosity level by repeating the flag"),
+        ];
+
+        assert_eq!(
+            flag_help_text(
+                &flags.iter().collect::<Vec<_>>(),
+                true,
+                &Scope {
+                    width: Some(50),
+                    ..Scope::default()
+                }
+            ),
+            "\t--name <string> : 名前を指定します\n\
+             \t-v, --verbose   : Increase verbosity level\n\
+             \t                  by repeating the flag\n\
+             \t-h, --help      : Show help\n"
+        );
+    }
+
+    #[test]
+    fn flag_long_help_text_test() {
+        let flags = [Flag::new("port", FlagType::Int)
+            .description("Port number")
+            .long_description("Port number to listen on")
+            .default_value("80")
+            .default_value_if("tls", "true", "443")];
+
+        assert_eq!(
+            flag_help_text(
+                &flags.iter().collect::<Vec<_>>(),
+                true,
+                &Scope {
+                    long_help: true,
+                    ..Scope::default()
+                }
+            ),
+            "\t--port <int>\n\
+             \t        Port number to listen on\n\
+             \t        [default: 80]\n\
+             \t        [default: 443 if --tls=true]\n\
+             \n\
+             \t-h, --help\n\
+             \t        Show help (see a summary with '-h')\n"
+        );
+    }
+
+    #[test]
+    fn render_template_test() {
+        let sections = HelpSections {
+            name: "cli".to_string(),
+            usage: "cli [OPTIONS]".to_string(),
+            flags: vec![(
+                "Flags".to_string(),
+                "\t-h, --help : Show help\n".to_string(),
+            )],
+            version: Some("1.0.0".to_string()),
+            ..HelpSections::default()
+        };
+        let template =
+            Some("{name} {version} {unknown}\n\nUSAGE: {usage}\n{flags}{commands}".to_string());
 
-    fn help(&self) {
-        println!("{}", self.help_text());
+        assert_eq!(
+            sections.render(&template, &Scope::default()),
+            "cli 1.0.0 {unknown}\n\nUSAGE: cli [OPTIONS]\n\t-h, --help : Show help\n"
+        );
     }
 }
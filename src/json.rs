@@ -0,0 +1,440 @@
+use crate::error::SpecError;
+
+/// Maximum nesting depth of arrays and objects, to bound the recursion of the parser
+const MAX_DEPTH: usize = 128;
+
+/// JSON value used by the spec export
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    /// Object keeping the order of the keys
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Parse JSON text
+    pub fn parse(text: &str) -> Result<Self, SpecError> {
+        let mut parser = Parser {
+            chars: text.chars().collect(),
+            pos: 0,
+            depth: 0,
+        };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.pos < parser.chars.len() {
+            return Err(parser.error_at(parser.pos, "unexpected trailing characters"));
+        }
+        Ok(value)
+    }
+
+    /// Get JSON text indented by two spaces
+    pub fn to_pretty_string(&self) -> String {
+        let mut out = String::new();
+        self.write(&mut out, 0);
+        out
+    }
+
+    fn write(&self, out: &mut String, indent: usize) {
+        match self {
+            Json::Null => out.push_str("null"),
+            Json::Bool(b) => out.push_str(&b.to_string()),
+            Json::Number(n) => out.push_str(&n.to_string()),
+            Json::String(s) => write_string(out, s),
+            Json::Array(items) if items.is_empty() => out.push_str("[]"),
+            Json::Array(items) => {
+                out.push_str("[\n");
+                for (i, item) in items.iter().enumerate() {
+                    out.push_str(&"  ".repeat(indent + 1));
+                    item.write(out, indent + 1);
+                    out.push_str(if i + 1 < items.len() { ",\n" } else { "\n" });
+                }
+                out.push_str(&"  ".repeat(indent));
+                out.push(']');
+            }
+            Json::Object(entries) if entries.is_empty() => out.push_str("{}"),
+            Json::Object(entries) => {
+                out.push_str("{\n");
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    out.push_str(&"  ".repeat(indent + 1));
+                    write_string(out, key);
+                    out.push_str(": ");
+                    value.write(out, indent + 1);
+                    out.push_str(if i + 1 < entries.len() { ",\n" } else { "\n" });
+                }
+                out.push_str(&"  ".repeat(indent));
+                out.push('}');
+            }
+        }
+    }
+}
+
+impl From<&str> for Json {
+    fn from(s: &str) -> Self {
+        Json::String(s.to_string())
+    }
+}
+
+impl From<&Option<String>> for Json {
+    fn from(s: &Option<String>) -> Self {
+        match s {
+            Some(s) => Json::String(s.clone()),
+            None => Json::Null,
+        }
+    }
+}
+
+impl From<&Vec<String>> for Json {
+    fn from(v: &Vec<String>) -> Self {
+        Json::Array(v.iter().map(|s| Json::String(s.clone())).collect())
+    }
+}
+
+/// Write the string quoted, escaping quotes, backslashes and control characters
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    /// Number of arrays and objects enclosing the current position
+    depth: usize,
+}
+
+impl Parser {
+    fn value(&mut self) -> Result<Json, SpecError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('n') => self.literal("null", Json::Null),
+            Some('t') => self.literal("true", Json::Bool(true)),
+            Some('f') => self.literal("false", Json::Bool(false)),
+            Some('"') => Ok(Json::String(self.string()?)),
+            Some(c @ ('[' | '{')) => {
+                if self.depth == MAX_DEPTH {
+                    return Err(self.error_at(self.pos, "too deeply nested"));
+                }
+                self.depth += 1;
+                let value = if c == '[' {
+                    self.array()
+                } else {
+                    self.object()
+                };
+                self.depth -= 1;
+                value
+            }
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            Some(_) => Err(self.error_at(self.pos, "expected a value")),
+            None => Err(self.error_at(self.pos, "unexpected end of input")),
+        }
+    }
+
+    fn literal(&mut self, word: &str, value: Json) -> Result<Json, SpecError> {
+        for expected in word.chars() {
+            if self.next() != Some(expected) {
+                return Err(self.error(&format!("expected \"{}\"", word)));
+            }
+        }
+        Ok(value)
+    }
+
+    fn number(&mut self) -> Result<Json, SpecError> {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if c.is_ascii_digit() || "+-.eE".contains(c) {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        match text.parse() {
+            Ok(number) if is_number(&text) => Ok(Json::Number(number)),
+            _ => Err(self.error_at(start, &format!("invalid number \"{}\"", text))),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, SpecError> {
+        self.expect('"')?;
+        let mut s = String::new();
+        loop {
+            match self.next() {
+                Some('"') => return Ok(s),
+                Some('\\') => match self.next() {
+                    Some('"') => s.push('"'),
+                    Some('\\') => s.push('\\'),
+                    Some('/') => s.push('/'),
+                    Some('b') => s.push('\u{8}'),
+                    Some('f') => s.push('\u{c}'),
+                    Some('n') => s.push('\n'),
+                    Some('r') => s.push('\r'),
+                    Some('t') => s.push('\t'),
+                    Some('u') => s.push(self.unicode_escape()?),
+                    _ => return Err(self.error("invalid escape sequence")),
+                },
+                Some(c) if (c as u32) < 0x20 => {
+                    return Err(self.error("control character in string"))
+                }
+                Some(c) => s.push(c),
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    /// Decode `\uXXXX` after the `\u`, combining surrogate pairs
+    fn unicode_escape(&mut self) -> Result<char, SpecError> {
+        let high = self.hex4()?;
+        let code = if (0xd800..0xdc00).contains(&high) {
+            if self.next() != Some('\\') || self.next() != Some('u') {
+                return Err(self.error("expected low surrogate"));
+            }
+            let low = self.hex4()?;
+            if !(0xdc00..0xe000).contains(&low) {
+                return Err(self.error("invalid low surrogate"));
+            }
+            0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00)
+        } else {
+            high
+        };
+        char::from_u32(code).ok_or_else(|| self.error("invalid unicode escape"))
+    }
+
+    fn hex4(&mut self) -> Result<u32, SpecError> {
+        let mut code = 0;
+        for _ in 0..4 {
+            let digit = self
+                .next()
+                .and_then(|c| c.to_digit(16))
+                .ok_or_else(|| self.error("invalid unicode escape"))?;
+            code = code * 16 + digit;
+        }
+        Ok(code)
+    }
+
+    fn array(&mut self) -> Result<Json, SpecError> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.pos += 1;
+            return Ok(Json::Array(items));
+        }
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some(']') => return Ok(Json::Array(items)),
+                _ => return Err(self.error("expected \",\" or \"]\"")),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, SpecError> {
+        self.expect('{')?;
+        let mut entries = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(Json::Object(entries));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some('"') {
+                return Err(self.error_at(self.pos, "expected a string key"));
+            }
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(':')?;
+            entries.push((key, self.value()?));
+            self.skip_whitespace();
+            match self.next() {
+                Some(',') => continue,
+                Some('}') => return Ok(Json::Object(entries)),
+                _ => return Err(self.error("expected \",\" or \"}\"")),
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), SpecError> {
+        if self.next() == Some(expected) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected \"{}\"", expected)))
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek();
+        if c.is_some() {
+            self.pos += 1;
+        }
+        c
+    }
+
+    /// Get line and column starting from 1 of the position
+    fn line_column(&self, pos: usize) -> (usize, usize) {
+        let before = &self.chars[..pos.min(self.chars.len())];
+        let line = before.iter().filter(|c| **c == '\n').count() + 1;
+        let column = before.iter().rev().take_while(|c| **c != '\n').count() + 1;
+        (line, column)
+    }
+
+    /// Error at the last read character
+    fn error(&self, message: &str) -> SpecError {
+        self.error_at(self.pos.saturating_sub(1), message)
+    }
+
+    fn error_at(&self, pos: usize, message: &str) -> SpecError {
+        let (line, column) = self.line_column(pos);
+        SpecError::Syntax {
+            line,
+            column,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// Check the number grammar of JSON, which is stricter than `f64::from_str`
+/// e.g. "01", "1." and ".5" are rejected
+fn is_number(text: &str) -> bool {
+    let digits = |s: &str| s.bytes().take_while(|b| b.is_ascii_digit()).count();
+
+    let text = text.strip_prefix('-').unwrap_or(text);
+    let int = digits(text);
+    if int == 0 || (int > 1 && text.starts_with('0')) {
+        return false;
+    }
+
+    let mut rest = &text[int..];
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let n = digits(fraction);
+        if n == 0 {
+            return false;
+        }
+        rest = &fraction[n..];
+    }
+    if let Some(exponent) = rest.strip_prefix(['e', 'E']) {
+        let exponent = exponent.strip_prefix(['+', '-']).unwrap_or(exponent);
+        let n = digits(exponent);
+        if n == 0 {
+            return false;
+        }
+        rest = &exponent[n..];
+    }
+    rest.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Json;
+    use crate::error::SpecError;
+
+    #[test]
+    fn to_pretty_string_test() {
+        let json = Json::Object(vec![
+            ("name".to_string(), Json::from("say \"hi\"\n")),
+            ("hidden".to_string(), Json::Bool(false)),
+            ("usage".to_string(), Json::Null),
+            ("aliases".to_string(), Json::Array(vec![Json::from("s")])),
+            ("flags".to_string(), Json::Array(vec![])),
+        ]);
+
+        assert_eq!(
+            json.to_pretty_string(),
+            "{\n  \"name\": \"say \\\"hi\\\"\\n\",\n  \"hidden\": false,\n  \"usage\": null,\n  \
+             \"aliases\": [\n    \"s\"\n  ],\n  \"flags\": []\n}"
+        );
+    }
+
+    #[test]
+    fn parse_test() {
+        let json = Json::parse(
+            "{\"name\": \"say \\\"hi\\\" \\u00e9\\ud83d\\ude00\", \"hidden\": true,\n\
+             \"usage\": null, \"port\": -1.5e1, \"aliases\": [\"s\", []], \"flags\": {}}",
+        );
+        assert_eq!(
+            json,
+            Ok(Json::Object(vec![
+                ("name".to_string(), Json::from("say \"hi\" \u{e9}\u{1f600}")),
+                ("hidden".to_string(), Json::Bool(true)),
+                ("usage".to_string(), Json::Null),
+                ("port".to_string(), Json::Number(-15.0)),
+                (
+                    "aliases".to_string(),
+                    Json::Array(vec![Json::from("s"), Json::Array(vec![])])
+                ),
+                ("flags".to_string(), Json::Object(vec![])),
+            ]))
+        );
+
+        assert_eq!(
+            Json::parse("{\n  \"name\": \"cli\"\n  \"hidden\": true\n}"),
+            Err(SpecError::Syntax {
+                line: 3,
+                column: 3,
+                message: "expected \",\" or \"}\"".to_string()
+            })
+        );
+        assert!(Json::parse("[1, 2,]").is_err());
+        assert!(Json::parse("\"unterminated").is_err());
+        assert!(Json::parse("true false").is_err());
+    }
+
+    #[test]
+    fn parse_number_test() {
+        for text in ["0", "-0", "10", "1.5", "-0.5e-3", "2E+10"] {
+            assert_eq!(Json::parse(text), Ok(Json::Number(text.parse().unwrap())));
+        }
+        for text in ["01", "-01", "1.", ".5", "-", "1e", "1e+", "+1", "1.5.2"] {
+            assert!(Json::parse(text).is_err(), "{}", text);
+        }
+        assert_eq!(
+            Json::parse("[01]"),
+            Err(SpecError::Syntax {
+                line: 1,
+                column: 2,
+                message: "invalid number \"01\"".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn parse_depth_test() {
+        let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        assert!(Json::parse(&nested(128)).is_ok());
+        assert_eq!(
+            Json::parse(&nested(100_000)),
+            Err(SpecError::Syntax {
+                line: 1,
+                column: 129,
+                message: "too deeply nested".to_string()
+            })
+        );
+    }
+}
@@ -1,15 +1,37 @@
 mod action;
 mod app;
+mod arg;
+mod color;
 mod command;
+mod completion;
+mod config;
 mod context;
 pub mod error;
 mod flag;
+mod from_context;
+mod group;
 mod help;
+mod json;
+mod manpage;
+mod markdown;
+mod reference;
+mod scope;
+mod spec;
 mod utils;
 
 pub use action::{Action, ActionWithResult};
 pub use app::App;
+pub use arg::Arg;
+pub use color::ColorChoice;
 pub use command::Command;
-pub use context::Context;
+pub use completion::{Completer, Shell};
+pub use context::{Context, ValueSource};
 pub use flag::{Flag, FlagType, FlagValue};
+pub use from_context::FromContext;
+pub use group::ArgGroup;
 use help::Help;
+use scope::Scope;
+pub use spec::{ArgSpec, CommandSpec, FlagSpec};
+
+#[cfg(feature = "derive")]
+pub use seahorse_derive::Seahorse;
@@ -0,0 +1,192 @@
+use crate::help::flag_details;
+use crate::reference::{flag_names, pages, Page};
+use crate::{App, Flag};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Write man page of the app in roff format
+pub(crate) fn write_manpage<W: io::Write>(app: &App, writer: &mut W) -> io::Result<()> {
+    let pages = pages(app);
+    writer.write_all(manpage(app, &pages[0], None).as_bytes())
+}
+
+/// Write man pages of the app and all sub commands into `dir`, named like `cli-remote-add.1`
+pub(crate) fn write_manpages(app: &App, dir: &Path) -> io::Result<()> {
+    let pages = pages(app);
+    for (i, page) in pages.iter().enumerate() {
+        // The parent page is the last page with the path one shorter
+        let parent = pages[..i]
+            .iter()
+            .rev()
+            .find(|p| p.path.len() + 1 == page.path.len());
+        let path = dir.join(format!("{}.1", page.name("-")));
+        fs::write(path, manpage(app, page, parent))?;
+    }
+    Ok(())
+}
+
+fn manpage(app: &App, page: &Page, parent: Option<&Page>) -> String {
+    let source = match &app.version {
+        Some(version) => format!("{} {}", app.name, version),
+        None => app.name.clone(),
+    };
+    let mut roff = format!(
+        ".TH \"{}\" \"1\" \"\" \"{}\" \"User Commands\"\n",
+        escape(&page.name("-").to_uppercase()),
+        escape(&source)
+    );
+
+    roff += ".SH NAME\n";
+    roff += &escape(&page.name("-"));
+    if let Some(description) = page.description.and_then(|d| d.lines().next()) {
+        roff += &format!(" \\- {}", escape(description));
+    }
+    roff += "\n";
+
+    roff += &format!(".SH SYNOPSIS\n{}\n", escape(&page.usage));
+
+    if let Some(description) = page.long_description.or(page.description) {
+        roff += ".SH DESCRIPTION\n";
+        roff += &paragraphs(description);
+    }
+
+    roff += ".SH OPTIONS\n";
+    roff += &options(&page.flags);
+    roff += ".TP\n\\fB\\-h\\fR, \\fB\\-\\-help\\fR\nShow help\n";
+
+    if !page.global_flags.is_empty() {
+        roff += ".SH GLOBAL OPTIONS\n";
+        roff += &options(&page.global_flags);
+    }
+
+    if !page.commands.is_empty() {
+        roff += ".SH COMMANDS\n";
+        for command in &page.commands {
+            let mut names = vec![command.name.as_str()];
+            names.extend(command.alias.iter().flatten().map(|a| a.as_str()));
+            let names = names
+                .iter()
+                .map(|n| format!("\\fB{}\\fR", escape(n)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            roff += &format!(".TP\n{}\n", names);
+            if let Some(description) = &command.description {
+                roff += &format!("{}\n.br\n", escape(description));
+            }
+            roff += &format!(
+                "See \\fB{}\\fR(1).\n",
+                escape(&page.child_name(command, "-"))
+            );
+        }
+    }
+
+    if let Some(examples) = page.examples {
+        roff += ".SH EXAMPLES\n.nf\n";
+        for example in examples {
+            roff += &format!("{}\n", escape(example));
+        }
+        roff += ".fi\n";
+    }
+
+    if parent.is_none() {
+        if let Some(author) = &app.author {
+            roff += &format!(".SH AUTHOR\n{}\n", escape(author));
+        }
+    }
+
+    if let Some(parent) = parent {
+        roff += &format!(".SH SEE ALSO\n\\fB{}\\fR(1)\n", escape(&parent.name("-")));
+    }
+
+    roff
+}
+
+/// Build `.TP` entries of the flags with names in bold and the value in italic
+fn options(flags: &[Flag]) -> String {
+    flags
+        .iter()
+        .map(|f| {
+            let mut names = flag_names(f)
+                .iter()
+                .map(|n| format!("\\fB{}\\fR", escape(n)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            if let Some(placeholder) = f.value_placeholder() {
+                names += &format!(" \\fI{}\\fR", escape(&placeholder));
+            }
+            let details = flag_details(f)
+                .iter()
+                .map(|d| escape(d))
+                .collect::<Vec<_>>()
+                .join("\n.br\n");
+            format!(".TP\n{}\n{}\n", names, details)
+        })
+        .collect()
+}
+
+/// Build paragraphs of the text separated by blank lines
+fn paragraphs(text: &str) -> String {
+    text.split("\n\n")
+        .map(|p| format!("{}\n", escape(p.trim())))
+        .collect::<Vec<_>>()
+        .join(".PP\n")
+}
+
+/// Escape backslashes and hyphens, and control characters at the start of lines
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\e")
+        .replace('-', "\\-")
+        .lines()
+        .map(|line| {
+            if line.starts_with('.') || line.starts_with('\'') {
+                format!("\\&{}", line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{manpage, pages};
+    use crate::{App, Command, Flag, FlagType};
+
+    #[test]
+    fn manpage_test() {
+        let app = App::new("cli")
+            .version("1.0.0")
+            .author("John Doe")
+            .description("Greeting tool")
+            .long_description("Greet people.\n\n.Names are read from arguments.")
+            .example("cli --name John")
+            .flag(
+                Flag::new("name", FlagType::String)
+                    .alias("n")
+                    .description("Name to greet")
+                    .default_value("world"),
+            )
+            .flag(
+                Flag::new("verbose", FlagType::Bool)
+                    .description("Show details")
+                    .global(),
+            )
+            .command(
+                Command::new("remote")
+                    .alias("r")
+                    .description("Manage remotes"),
+            );
+        let pages = pages(&app);
+
+        assert_eq!(
+            manpage(&app, &pages[0], None),
+            include_str!("snapshots/manpage.1")
+        );
+        assert_eq!(
+            manpage(&app, &pages[1], Some(&pages[0])),
+            include_str!("snapshots/manpage-remote.1")
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use crate::reference::{flag_names, pages, Page};
+use crate::{App, Flag};
+use std::io;
+
+/// Write reference documentation of the app in Markdown, one page per command path
+pub(crate) fn write_markdown<W: io::Write>(app: &App, writer: &mut W) -> io::Result<()> {
+    let pages = pages(app)
+        .iter()
+        .map(|page| format!("{}\n", markdown_page(page).trim_end()))
+        .collect::<Vec<_>>();
+    writer.write_all(pages.join("\n").as_bytes())
+}
+
+fn markdown_page(page: &Page) -> String {
+    let mut md = format!("# {}\n\n", page.name(" "));
+
+    if let Some(description) = page.long_description.or(page.description) {
+        md += &format!("{}\n\n", description);
+    }
+
+    md += &format!("## Usage\n\n```\n{}\n```\n\n", page.usage);
+
+    md += "## Flags\n\n";
+    md += &flags_table(&page.flags, true);
+
+    if !page.global_flags.is_empty() {
+        md += "## Global Flags\n\n";
+        md += &flags_table(&page.global_flags, false);
+    }
+
+    if !page.commands.is_empty() {
+        md += "## Commands\n\n";
+        for command in &page.commands {
+            let name = page.child_name(command, " ");
+            md += &format!("- [`{}`](#{})", name, page.child_name(command, "-"));
+            if let Some(alias) = &command.alias {
+                let alias = alias
+                    .iter()
+                    .map(|a| format!("`{}`", a))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                md += &format!(" (aliases: {})", alias);
+            }
+            if let Some(description) = command.description.as_ref().and_then(|d| d.lines().next()) {
+                md += &format!(": {}", description);
+            }
+            md += "\n";
+        }
+        md += "\n";
+    }
+
+    if let Some(examples) = page.examples {
+        md += &format!("## Examples\n\n```\n{}\n```\n\n", examples.join("\n"));
+    }
+
+    md
+}
+
+/// Build table of the flags with name, aliases, type, default, env and description columns
+fn flags_table(flags: &[Flag], help_flag: bool) -> String {
+    let mut table = "| Name | Aliases | Type | Default | Env | Description |\n\
+                     | --- | --- | --- | --- | --- | --- |\n"
+        .to_string();
+
+    for flag in flags {
+        let names = flag_names(flag);
+        let mut defaults = flag
+            .default_value
+            .iter()
+            .map(|d| format!("`{}`", d))
+            .collect::<Vec<_>>();
+        defaults.extend(
+            flag.default_value_if
+                .iter()
+                .flatten()
+                .map(|(other, value, default)| format!("`{}` if `--{}={}`", default, other, value)),
+        );
+
+        table += &format!(
+            "| `{}` | {} | {} | {} | {} | {} |\n",
+            names[0],
+            names[1..]
+                .iter()
+                .map(|n| format!("`{}`", n))
+                .collect::<Vec<_>>()
+                .join(", "),
+            type_name(flag),
+            defaults.join(", "),
+            flag.env
+                .as_ref()
+                .map(|e| format!("`{}`", e))
+                .unwrap_or_default(),
+            cell(flag.description.as_deref().unwrap_or_default())
+        );
+    }
+
+    if help_flag {
+        table += "| `--help` | `-h` | bool |  |  | Show help |\n";
+    }
+    table + "\n"
+}
+
+/// Get the type of the flag value, with `[]` for multiple flags
+fn type_name(flag: &Flag) -> String {
+    let name = flag.flag_type.name();
+    if flag.multiple {
+        format!("{}[]", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Escape the text to fit in a table cell
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write_markdown;
+    use crate::{App, Command, Flag, FlagType};
+
+    #[test]
+    fn markdown_test() {
+        let app = App::new("cli")
+            .description("Greeting tool")
+            .env_prefix("CLI")
+            .flag(
+                Flag::new("name", FlagType::String)
+                    .alias("n")
+                    .description("Name to greet | nickname")
+                    .default_value("world"),
+            )
+            .flag(
+                Flag::new("verbose", FlagType::Bool)
+                    .description("Show details")
+                    .global()
+                    .no_env_prefix(),
+            )
+            .command(
+                Command::new("remote")
+                    .alias("r")
+                    .description("Manage remotes")
+                    .command(
+                        Command::new("add")
+                            .description("Add a remote")
+                            .example("cli remote add origin")
+                            .flag(Flag::new("port", FlagType::Uint).multiple()),
+                    ),
+            );
+
+        let mut buf = Vec::new();
+        write_markdown(&app, &mut buf).unwrap();
+        assert_eq!(
+            String::from_utf8(buf).unwrap(),
+            include_str!("snapshots/reference.md")
+        );
+    }
+}
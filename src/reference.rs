@@ -0,0 +1,105 @@
+use crate::{App, Command, Flag, Scope};
+
+/// Page of the reference documentation for the app or a command
+pub(crate) struct Page<'a> {
+    /// Command path including the app name
+    pub path: Vec<String>,
+    pub description: Option<&'a String>,
+    pub long_description: Option<&'a String>,
+    pub usage: String,
+    /// Flags of the command
+    pub flags: Vec<Flag>,
+    /// Flags inherited from the parent commands
+    pub global_flags: Vec<Flag>,
+    pub commands: Vec<&'a Command>,
+    pub examples: &'a Option<Vec<String>>,
+}
+
+impl Page<'_> {
+    /// Get the page name joined by `separator`, e.g. `cli-remote-add`
+    pub fn name(&self, separator: &str) -> String {
+        self.path.join(separator)
+    }
+
+    /// Get the page name of the sub command
+    pub fn child_name(&self, command: &Command, separator: &str) -> String {
+        format!("{}{}{}", self.name(separator), separator, command.name)
+    }
+}
+
+/// Walk the commands tree of the app to build pages, excluding hidden commands and flags
+pub(crate) fn pages(app: &App) -> Vec<Page<'_>> {
+    let scope = Scope {
+        env_prefix: app.env_prefix.clone(),
+        ..Scope::default()
+    };
+
+    let mut pages = vec![Page {
+        path: vec![app.name.clone()],
+        description: app.description.as_ref(),
+        long_description: app.long_description.as_ref(),
+        usage: app.usage_text(&scope),
+        flags: scope.with_env_prefix(visible_flags(&app.flags), None),
+        global_flags: Vec::new(),
+        commands: visible_commands(&app.commands),
+        examples: &app.examples,
+    }];
+
+    let scope = scope.child(&app.name, &app.flags);
+    for command in visible_commands(&app.commands) {
+        collect_pages(command, &scope, &mut pages);
+    }
+    pages
+}
+
+fn collect_pages<'a>(command: &'a Command, scope: &Scope, pages: &mut Vec<Page<'a>>) {
+    let mut path = scope.path.clone();
+    path.push(command.name.clone());
+
+    let global_flags = scope
+        .inherited_flags(&command.flags)
+        .into_iter()
+        .filter(|f| !f.hidden)
+        .collect();
+    pages.push(Page {
+        path,
+        description: command.description.as_ref(),
+        long_description: command.long_description.as_ref(),
+        usage: command.usage_text(scope),
+        flags: scope.with_env_prefix(visible_flags(&command.flags), Some(&command.name)),
+        global_flags: scope.with_env_prefix(global_flags, Some(&command.name)),
+        commands: visible_commands(&command.commands),
+        examples: &command.examples,
+    });
+
+    let scope = scope.child(&command.name, &command.flags);
+    for sub in visible_commands(&command.commands) {
+        collect_pages(sub, &scope, pages);
+    }
+}
+
+pub(crate) fn visible_flags(flags: &Option<Vec<Flag>>) -> Vec<Flag> {
+    flags
+        .iter()
+        .flatten()
+        .filter(|f| !f.hidden)
+        .cloned()
+        .collect()
+}
+
+pub(crate) fn visible_commands(commands: &Option<Vec<Command>>) -> Vec<&Command> {
+    commands.iter().flatten().filter(|c| !c.hidden).collect()
+}
+
+/// Get the flag names as written on the command line, e.g. `--name` and `-n`
+pub(crate) fn flag_names(flag: &Flag) -> Vec<String> {
+    let mut names = vec![format!("--{}", flag.name)];
+    names.extend(flag.alias.iter().flatten().map(|a| {
+        if a.len() > 1 {
+            format!("--{}", a)
+        } else {
+            format!("-{}", a)
+        }
+    }));
+    names
+}
@@ -0,0 +1,176 @@
+use crate::color::Stream;
+use crate::config::Config;
+use crate::help::{help_width, warning_text};
+use crate::{App, Flag, ValueSource};
+use std::env;
+
+/// Command path, global flags and app settings inherited while dispatching to nested commands
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Scope {
+    /// Names of the app and the parent commands
+    pub path: Vec<String>,
+    /// Global flags of the app and the parent commands
+    pub flags: Vec<Flag>,
+    /// Width to wrap help text
+    pub width: Option<usize>,
+    /// Color help output written to stdout
+    pub color: bool,
+    /// Color error and warning output written to stderr
+    pub error_color: bool,
+    /// Show long help requested by `--help` instead of the summary by `-h`
+    pub long_help: bool,
+    /// Show hidden flags and commands in help requested by `--help-all`
+    pub show_hidden: bool,
+    /// Config file of the app
+    pub config: Option<Config>,
+    /// Accept built-in `--debug-config` flag
+    pub debug_config: bool,
+    /// Prefix of the environment variables read by all flags
+    pub env_prefix: Option<String>,
+}
+
+impl Scope {
+    /// Create the root scope with settings of the app
+    /// `--color` flag in `args` takes precedence over the color choice of the app
+    /// Problems with the config file are reported as warnings
+    pub fn new(app: &App, args: &[String]) -> Self {
+        let color = app.color_choice(args);
+        let mut scope = Self {
+            width: help_width(app.help_width),
+            color: color.enabled(Stream::Stdout),
+            error_color: color.enabled(Stream::Stderr),
+            debug_config: app.debug_config_flag,
+            env_prefix: app.env_prefix.clone(),
+            ..Self::default()
+        };
+
+        if let Some(path) = &app.config_file {
+            match Config::load(path) {
+                Ok(config) => {
+                    for error in config.iter().flat_map(|c| &c.errors) {
+                        eprintln!("{}", warning_text(error, &scope));
+                    }
+                    scope.config = config;
+                }
+                Err(e) => {
+                    let message = format!("{}: {}", path, e);
+                    eprintln!("{}", warning_text(&message, &scope));
+                }
+            }
+        }
+
+        scope
+    }
+
+    /// Create the scope for sub commands of the app or command named `name`
+    /// Global flags get environment variable names of the app or command defining them
+    pub fn child(&self, name: &str, flags: &Option<Vec<Flag>>) -> Self {
+        let mut path = self.path.clone();
+        path.push(name.to_string());
+
+        let mut inherited = self.inherited_flags(flags);
+        if let Some(flags) = flags {
+            let globals = flags.iter().filter(|f| f.global).cloned().collect();
+            // The root scope is the one of the app, whose name is not part of the variable name
+            let command = Some(name).filter(|_| !self.path.is_empty());
+            inherited.extend(self.with_env_prefix(globals, command));
+        }
+
+        Self {
+            path,
+            flags: inherited,
+            ..self.clone()
+        }
+    }
+
+    /// Create the scope to show long help
+    pub fn long_help(&self) -> Self {
+        Self {
+            long_help: true,
+            ..self.clone()
+        }
+    }
+
+    /// Create the scope to show long help including hidden flags and commands
+    pub fn help_all(&self) -> Self {
+        Self {
+            long_help: true,
+            show_hidden: true,
+            ..self.clone()
+        }
+    }
+
+    /// Get the full path of the command named `name` in this scope
+    pub fn command_path(&self, name: &str) -> String {
+        let mut path = self.path.clone();
+        path.push(name.to_string());
+        path.join(" ")
+    }
+
+    /// Get global flags that are not overridden by `flags` of the command
+    pub fn inherited_flags(&self, flags: &Option<Vec<Flag>>) -> Vec<Flag> {
+        self.flags
+            .iter()
+            .filter(|g| match flags {
+                Some(flags) => flags.iter().all(|f| f.name != g.name),
+                None => true,
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Get value of the flag not specified on the command line
+    /// from the environment variable or the config file section of `command`, in this order
+    pub fn fallback_value(
+        &self,
+        flag: &Flag,
+        command: Option<&str>,
+    ) -> Option<(String, ValueSource)> {
+        if let Some(var) = &flag.env {
+            if let Ok(value) = env::var(var) {
+                return Some((value, ValueSource::Environment(var.clone())));
+            }
+        }
+
+        // The app name is not part of the section
+        let mut path = self.path.iter().skip(1).cloned().collect::<Vec<_>>();
+        path.extend(command.map(|c| c.to_string()));
+        let config = self.config.as_ref()?;
+        config.get(&path, &flag.name).map(|e| {
+            let source = ValueSource::ConfigFile(config.path.clone(), e.line);
+            (e.value.clone(), source)
+        })
+    }
+
+    /// Set environment variable names derived from the env prefix of the app
+    /// to `flags` of `command` without explicit one,
+    /// e.g. `MYTOOL_REMOTE_ADD_NAME` for `--name` of `remote add`
+    pub fn with_env_prefix(&self, mut flags: Vec<Flag>, command: Option<&str>) -> Vec<Flag> {
+        if let Some(prefix) = &self.env_prefix {
+            // The app name is not part of the variable name
+            let mut words = vec![prefix.as_str()];
+            words.extend(self.path.iter().skip(1).map(|p| p.as_str()));
+            words.extend(command);
+
+            for flag in flags
+                .iter_mut()
+                .filter(|f| f.env.is_none() && !f.no_env_prefix)
+            {
+                let mut words = words.clone();
+                words.push(&flag.name);
+                flag.env = Some(words.join("_").to_uppercase().replace('-', "_"));
+            }
+        }
+        flags
+    }
+
+    /// Get `flags` of the command together with the inherited global flags
+    pub fn flags_with(&self, flags: &Option<Vec<Flag>>) -> Option<Vec<Flag>> {
+        let inherited = self.inherited_flags(flags);
+        match flags {
+            Some(flags) => Some(flags.iter().cloned().chain(inherited).collect()),
+            None if inherited.is_empty() => None,
+            None => Some(inherited),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+_cli() {
+    local cur prev opts cmd_path="" i
+    cur="${COMP_WORDS[COMP_CWORD]}"
+    prev="${COMP_WORDS[COMP_CWORD-1]}"
+
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case "${cmd_path}:${COMP_WORDS[i]}" in
+            ":remote" | ":r")
+                cmd_path="remote"
+                ;;
+            "remote:add")
+                cmd_path="remote add"
+                ;;
+        esac
+    done
+
+    case "${cmd_path}" in
+        "")
+            case "${prev}" in
+                --name | -n)
+                    return
+                    ;;
+            esac
+            opts="--name -n --verbose -h --help remote r"
+            ;;
+        "remote")
+            opts="--verbose -h --help add"
+            ;;
+        "remote add")
+            case "${prev}" in
+                --url)
+                    return
+                    ;;
+            esac
+            opts="--url --verbose -h --help"
+            ;;
+    esac
+
+    COMPREPLY=($(compgen -W "${opts}" -- "${cur}"))
+}
+
+complete -o default -F _cli cli
@@ -0,0 +1,27 @@
+function __fish_cli_using_path
+    set -l cmd_path ""
+    set -l words (commandline -opc)
+    set -e words[1]
+    for word in $words
+        switch "$cmd_path:$word"
+            case ":remote" ":r"
+                set cmd_path "remote"
+            case "remote:add"
+                set cmd_path "remote add"
+        end
+    end
+    test "$cmd_path" = "$argv[1]"
+end
+
+complete -c cli -f
+complete -c cli -n '__fish_cli_using_path ""' -a remote -d 'Manage remotes'
+complete -c cli -n '__fish_cli_using_path ""' -a r -d 'Manage remotes'
+complete -c cli -n '__fish_cli_using_path ""' -l name -s n -r -F -d 'Name to greet'
+complete -c cli -n '__fish_cli_using_path ""' -l verbose -d 'Show details'
+complete -c cli -n '__fish_cli_using_path ""' -s h -l help -d 'Show help'
+complete -c cli -n '__fish_cli_using_path "remote"' -a add -d 'Add a remote'
+complete -c cli -n '__fish_cli_using_path "remote"' -l verbose -d 'Show details'
+complete -c cli -n '__fish_cli_using_path "remote"' -s h -l help -d 'Show help'
+complete -c cli -n '__fish_cli_using_path "remote add"' -l url -r -F -d 'Remote\'s URL'
+complete -c cli -n '__fish_cli_using_path "remote add"' -l verbose -d 'Show details'
+complete -c cli -n '__fish_cli_using_path "remote add"' -s h -l help -d 'Show help'
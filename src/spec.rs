@@ -0,0 +1,579 @@
+use crate::error::SpecError;
+use crate::json::Json;
+use crate::{App, Arg, Command, Flag, FlagType};
+
+/// `CommandSpec` type.
+///
+/// Machine-readable description of the app or a command and its sub commands
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CommandSpec {
+    pub name: String,
+    pub description: Option<String>,
+    pub usage: Option<String>,
+    /// Version of the app, `None` for commands
+    pub version: Option<String>,
+    /// Author of the app, `None` for commands
+    pub author: Option<String>,
+    pub aliases: Vec<String>,
+    pub hidden: bool,
+    pub flags: Vec<FlagSpec>,
+    pub args: Vec<ArgSpec>,
+    pub commands: Vec<CommandSpec>,
+}
+
+/// `FlagSpec` type.
+///
+/// Machine-readable description of a flag
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlagSpec {
+    pub name: String,
+    pub description: Option<String>,
+    pub flag_type: FlagType,
+    pub aliases: Vec<String>,
+    pub multiple: bool,
+    pub required: bool,
+    pub global: bool,
+    pub hidden: bool,
+    pub default_value: Option<String>,
+    pub env: Option<String>,
+    pub possible_values: Vec<String>,
+}
+
+/// `ArgSpec` type.
+///
+/// Machine-readable description of a positional argument
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArgSpec {
+    pub name: String,
+    pub required: bool,
+    pub multiple: bool,
+}
+
+impl CommandSpec {
+    /// Parse JSON text in the format written by `to_json`
+    /// Only `name` of commands, flags and arguments and `type` of flags are required
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::CommandSpec;
+    ///
+    /// let spec = CommandSpec::from_json(r#"{"name": "cli", "commands": [{"name": "hello"}]}"#)
+    ///     .unwrap();
+    /// assert_eq!(spec.commands[0].name, "hello");
+    /// ```
+    pub fn from_json(text: &str) -> Result<Self, SpecError> {
+        Self::from_json_value(&Json::parse(text)?, String::new())
+    }
+
+    fn from_json_value(value: &Json, path: String) -> Result<Self, SpecError> {
+        let fields = Fields::new(value, path)?;
+        let spec = Self {
+            name: fields.string("name")?,
+            description: fields.optional_string("description")?,
+            usage: fields.optional_string("usage")?,
+            version: fields.optional_string("version")?,
+            author: fields.optional_string("author")?,
+            aliases: fields.strings("aliases")?,
+            hidden: fields.bool("hidden")?,
+            flags: fields.objects("flags", FlagSpec::from_json_value)?,
+            args: fields.objects("args", ArgSpec::from_json_value)?,
+            commands: fields.objects("commands", Self::from_json_value)?,
+        };
+
+        if let Some(i) = spec.aliases.iter().position(|a| a.is_empty()) {
+            return Err(fields.invalid(&format!("aliases[{}]", i), "a non-empty alias"));
+        }
+        for (i, flag) in spec.flags.iter().enumerate() {
+            if spec.flags[..i].iter().any(|f| f.name == flag.name) {
+                return Err(fields.invalid(&format!("flags[{}].name", i), "a unique flag name"));
+            }
+        }
+        for (i, command) in spec.commands.iter().enumerate() {
+            let field = format!("commands[{}].name", i);
+            if command.name.is_empty() {
+                return Err(fields.invalid(&field, "a non-empty command name"));
+            }
+            if command.name.contains(char::is_whitespace) {
+                return Err(fields.invalid(&field, "a command name without whitespace"));
+            }
+            if spec.commands[..i].iter().any(|c| c.name == command.name) {
+                return Err(fields.invalid(&field, "a unique command name"));
+            }
+        }
+        Ok(spec)
+    }
+
+    /// Get JSON text of the spec
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use seahorse::App;
+    ///
+    /// let json = App::new("cli").spec().to_json();
+    /// ```
+    pub fn to_json(&self) -> String {
+        self.to_json_value().to_pretty_string()
+    }
+
+    pub(crate) fn to_json_value(&self) -> Json {
+        Json::Object(vec![
+            ("name".to_string(), Json::from(self.name.as_str())),
+            ("description".to_string(), Json::from(&self.description)),
+            ("usage".to_string(), Json::from(&self.usage)),
+            ("version".to_string(), Json::from(&self.version)),
+            ("author".to_string(), Json::from(&self.author)),
+            ("aliases".to_string(), Json::from(&self.aliases)),
+            ("hidden".to_string(), Json::Bool(self.hidden)),
+            (
+                "flags".to_string(),
+                Json::Array(self.flags.iter().map(|f| f.to_json_value()).collect()),
+            ),
+            (
+                "args".to_string(),
+                Json::Array(self.args.iter().map(|a| a.to_json_value()).collect()),
+            ),
+            (
+                "commands".to_string(),
+                Json::Array(self.commands.iter().map(|c| c.to_json_value()).collect()),
+            ),
+        ])
+    }
+}
+
+impl FlagSpec {
+    fn from_json_value(value: &Json, path: String) -> Result<Self, SpecError> {
+        let fields = Fields::new(value, path)?;
+        let name = fields.string("name")?;
+        if name.is_empty() {
+            return Err(fields.invalid("name", "a non-empty flag name"));
+        }
+        if name.starts_with('-') || name.contains('=') || name.contains(char::is_whitespace) {
+            return Err(fields.invalid(
+                "name",
+                "a flag name without \"-\" prefix, \"=\" or whitespaces",
+            ));
+        }
+        let flag_type = FlagType::from_name(&fields.string("type")?)
+            .ok_or_else(|| fields.invalid("type", "one of bool, string, int, uint or float"))?;
+
+        let aliases = fields.strings("aliases")?;
+        if let Some(i) = aliases.iter().position(|a| a.is_empty()) {
+            return Err(fields.invalid(&format!("aliases[{}]", i), "a non-empty alias"));
+        }
+
+        Ok(Self {
+            name,
+            description: fields.optional_string("description")?,
+            flag_type,
+            aliases,
+            multiple: fields.bool("multiple")?,
+            required: fields.bool("required")?,
+            global: fields.bool("global")?,
+            hidden: fields.bool("hidden")?,
+            default_value: fields.optional_string("default_value")?,
+            env: fields.optional_string("env")?,
+            possible_values: fields.strings("possible_values")?,
+        })
+    }
+
+    pub(crate) fn to_json_value(&self) -> Json {
+        Json::Object(vec![
+            ("name".to_string(), Json::from(self.name.as_str())),
+            ("description".to_string(), Json::from(&self.description)),
+            ("type".to_string(), Json::from(self.flag_type.name())),
+            ("aliases".to_string(), Json::from(&self.aliases)),
+            ("multiple".to_string(), Json::Bool(self.multiple)),
+            ("required".to_string(), Json::Bool(self.required)),
+            ("global".to_string(), Json::Bool(self.global)),
+            ("hidden".to_string(), Json::Bool(self.hidden)),
+            ("default_value".to_string(), Json::from(&self.default_value)),
+            ("env".to_string(), Json::from(&self.env)),
+            (
+                "possible_values".to_string(),
+                Json::from(&self.possible_values),
+            ),
+        ])
+    }
+}
+
+impl ArgSpec {
+    fn from_json_value(value: &Json, path: String) -> Result<Self, SpecError> {
+        let fields = Fields::new(value, path)?;
+        Ok(Self {
+            name: fields.string("name")?,
+            required: fields.bool("required")?,
+            multiple: fields.bool("multiple")?,
+        })
+    }
+
+    pub(crate) fn to_json_value(&self) -> Json {
+        Json::Object(vec![
+            ("name".to_string(), Json::from(self.name.as_str())),
+            ("required".to_string(), Json::Bool(self.required)),
+            ("multiple".to_string(), Json::Bool(self.multiple)),
+        ])
+    }
+}
+
+/// Fields of a JSON object, reporting errors with the path of the field
+/// Missing fields and `null` values are treated the same
+struct Fields<'a> {
+    path: String,
+    entries: &'a [(String, Json)],
+}
+
+impl<'a> Fields<'a> {
+    fn new(value: &'a Json, path: String) -> Result<Self, SpecError> {
+        match value {
+            Json::Object(entries) => Ok(Self { path, entries }),
+            _ => Err(SpecError::InvalidField {
+                field: if path.is_empty() {
+                    "(root)".to_string()
+                } else {
+                    path
+                },
+                expected: "an object".to_string(),
+            }),
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<&'a Json> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v)
+            .filter(|v| **v != Json::Null)
+    }
+
+    fn field(&self, key: &str) -> String {
+        if self.path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", self.path, key)
+        }
+    }
+
+    fn invalid(&self, key: &str, expected: &str) -> SpecError {
+        SpecError::InvalidField {
+            field: self.field(key),
+            expected: expected.to_string(),
+        }
+    }
+
+    fn string(&self, key: &str) -> Result<String, SpecError> {
+        self.optional_string(key)?
+            .ok_or_else(|| self.invalid(key, "a string"))
+    }
+
+    fn optional_string(&self, key: &str) -> Result<Option<String>, SpecError> {
+        match self.get(key) {
+            None => Ok(None),
+            Some(Json::String(s)) => Ok(Some(s.clone())),
+            Some(_) => Err(self.invalid(key, "a string")),
+        }
+    }
+
+    fn bool(&self, key: &str) -> Result<bool, SpecError> {
+        match self.get(key) {
+            None => Ok(false),
+            Some(Json::Bool(b)) => Ok(*b),
+            Some(_) => Err(self.invalid(key, "a boolean")),
+        }
+    }
+
+    fn array(&self, key: &str) -> Result<&'a [Json], SpecError> {
+        match self.get(key) {
+            None => Ok(&[]),
+            Some(Json::Array(items)) => Ok(items),
+            Some(_) => Err(self.invalid(key, "an array")),
+        }
+    }
+
+    fn strings(&self, key: &str) -> Result<Vec<String>, SpecError> {
+        self.array(key)?
+            .iter()
+            .enumerate()
+            .map(|(i, item)| match item {
+                Json::String(s) => Ok(s.clone()),
+                _ => Err(self.invalid(&format!("{}[{}]", key, i), "a string")),
+            })
+            .collect()
+    }
+
+    fn objects<T>(
+        &self,
+        key: &str,
+        parse: fn(&Json, String) -> Result<T, SpecError>,
+    ) -> Result<Vec<T>, SpecError> {
+        self.array(key)?
+            .iter()
+            .enumerate()
+            .map(|(i, item)| parse(item, self.field(&format!("{}[{}]", key, i))))
+            .collect()
+    }
+}
+
+impl From<&App> for CommandSpec {
+    fn from(app: &App) -> Self {
+        Self {
+            name: app.name.clone(),
+            description: app.description.clone(),
+            usage: app.usage.clone(),
+            version: app.version.clone(),
+            author: app.author.clone(),
+            flags: app.flags.iter().flatten().map(FlagSpec::from).collect(),
+            args: app.args.iter().flatten().map(ArgSpec::from).collect(),
+            commands: app
+                .commands
+                .iter()
+                .flatten()
+                .map(CommandSpec::from)
+                .collect(),
+            ..Self::default()
+        }
+    }
+}
+
+impl From<&Command> for CommandSpec {
+    fn from(command: &Command) -> Self {
+        Self {
+            name: command.name.clone(),
+            description: command.description.clone(),
+            usage: command.usage.clone(),
+            aliases: command.alias.clone().unwrap_or_default(),
+            hidden: command.hidden,
+            flags: command.flags.iter().flatten().map(FlagSpec::from).collect(),
+            args: command.args.iter().flatten().map(ArgSpec::from).collect(),
+            commands: command
+                .commands
+                .iter()
+                .flatten()
+                .map(CommandSpec::from)
+                .collect(),
+            ..Self::default()
+        }
+    }
+}
+
+impl From<&Flag> for FlagSpec {
+    fn from(flag: &Flag) -> Self {
+        Self {
+            name: flag.name.clone(),
+            description: flag.description.clone(),
+            flag_type: flag.flag_type.clone(),
+            aliases: flag.alias.clone().unwrap_or_default(),
+            multiple: flag.multiple,
+            required: flag.required,
+            global: flag.global,
+            hidden: flag.hidden,
+            default_value: flag.default_value.clone(),
+            env: flag.env.clone(),
+            possible_values: flag.possible_values.clone().unwrap_or_default(),
+        }
+    }
+}
+
+impl From<&Arg> for ArgSpec {
+    fn from(arg: &Arg) -> Self {
+        Self {
+            name: arg.name.clone(),
+            required: arg.required,
+            multiple: arg.multiple,
+        }
+    }
+}
+
+impl From<CommandSpec> for App {
+    fn from(spec: CommandSpec) -> Self {
+        Self {
+            name: spec.name,
+            description: spec.description,
+            usage: spec.usage,
+            version: spec.version,
+            author: spec.author,
+            flags: non_empty(spec.flags.into_iter().map(Flag::from).collect()),
+            args: non_empty(spec.args.into_iter().map(Arg::from).collect()),
+            commands: non_empty(spec.commands.into_iter().map(Command::from).collect()),
+            ..Self::default()
+        }
+    }
+}
+
+impl From<CommandSpec> for Command {
+    fn from(spec: CommandSpec) -> Self {
+        Self {
+            name: spec.name,
+            description: spec.description,
+            usage: spec.usage,
+            alias: non_empty(spec.aliases),
+            hidden: spec.hidden,
+            flags: non_empty(spec.flags.into_iter().map(Flag::from).collect()),
+            args: non_empty(spec.args.into_iter().map(Arg::from).collect()),
+            commands: non_empty(spec.commands.into_iter().map(Command::from).collect()),
+            ..Self::default()
+        }
+    }
+}
+
+impl From<FlagSpec> for Flag {
+    fn from(spec: FlagSpec) -> Self {
+        Self {
+            description: spec.description,
+            alias: non_empty(spec.aliases),
+            multiple: spec.multiple,
+            required: spec.required,
+            global: spec.global,
+            hidden: spec.hidden,
+            default_value: spec.default_value,
+            env: spec.env,
+            possible_values: non_empty(spec.possible_values),
+            ..Flag::new(spec.name, spec.flag_type)
+        }
+    }
+}
+
+impl From<ArgSpec> for Arg {
+    fn from(spec: ArgSpec) -> Self {
+        Self {
+            required: spec.required,
+            multiple: spec.multiple,
+            ..Arg::new(spec.name)
+        }
+    }
+}
+
+fn non_empty<T>(items: Vec<T>) -> Option<Vec<T>> {
+    if items.is_empty() {
+        None
+    } else {
+        Some(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CommandSpec;
+    use crate::error::SpecError;
+    use crate::{App, Arg, Command, Flag, FlagType};
+
+    #[test]
+    fn to_json_test() {
+        let app = App::new("cli")
+            .version("1.0.0")
+            .description("Greeting tool")
+            .flag(
+                Flag::new("name", FlagType::String)
+                    .alias("n")
+                    .default_value("world"),
+            )
+            .command(
+                Command::new("remote")
+                    .alias("r")
+                    .arg(Arg::new("NAME").required()),
+            );
+
+        assert_eq!(app.spec().to_json(), include_str!("snapshots/spec.json"));
+    }
+
+    #[test]
+    fn from_json_test() {
+        let spec = CommandSpec::from_json(include_str!("snapshots/spec.json")).unwrap();
+        assert_eq!(spec.commands[0].aliases, vec!["r".to_string()]);
+        assert_eq!(spec.flags[0].flag_type, FlagType::String);
+        assert_eq!(spec.to_json(), include_str!("snapshots/spec.json"));
+
+        let spec = CommandSpec::from_json(r#"{"name": "cli", "flags": [{"name": "port"}]}"#);
+        assert_eq!(
+            spec,
+            Err(SpecError::InvalidField {
+                field: "flags[0].type".to_string(),
+                expected: "a string".to_string()
+            })
+        );
+
+        let spec = CommandSpec::from_json(
+            r#"{"name": "cli", "commands": [{"name": "a", "aliases": ["x", 1]}]}"#,
+        );
+        assert_eq!(
+            spec,
+            Err(SpecError::InvalidField {
+                field: "commands[0].aliases[1]".to_string(),
+                expected: "a string".to_string()
+            })
+        );
+
+        let spec = CommandSpec::from_json(
+            r#"{"name": "cli", "commands": [{"name": "a"}, {"name": "a"}]}"#,
+        );
+        assert_eq!(
+            spec,
+            Err(SpecError::InvalidField {
+                field: "commands[1].name".to_string(),
+                expected: "a unique command name".to_string()
+            })
+        );
+        let spec =
+            CommandSpec::from_json(r#"{"name": "cli", "commands": [{"name": "remote add"}]}"#);
+        assert_eq!(
+            spec,
+            Err(SpecError::InvalidField {
+                field: "commands[0].name".to_string(),
+                expected: "a command name without whitespace".to_string()
+            })
+        );
+
+        let spec = CommandSpec::from_json(
+            r#"{"name": "cli", "flags": [
+                {"name": "port", "type": "int"},
+                {"name": "port", "type": "string"}
+            ]}"#,
+        );
+        assert_eq!(
+            spec,
+            Err(SpecError::InvalidField {
+                field: "flags[1].name".to_string(),
+                expected: "a unique flag name".to_string()
+            })
+        );
+
+        let spec = CommandSpec::from_json(r#"{"name": "cli", "flags": [{"name": ""}]}"#);
+        assert_eq!(
+            spec,
+            Err(SpecError::InvalidField {
+                field: "flags[0].name".to_string(),
+                expected: "a non-empty flag name".to_string()
+            })
+        );
+        let spec = CommandSpec::from_json(
+            r#"{"name": "cli", "flags": [{"name": "port", "type": "int", "aliases": [""]}]}"#,
+        );
+        assert_eq!(
+            spec,
+            Err(SpecError::InvalidField {
+                field: "flags[0].aliases[0]".to_string(),
+                expected: "a non-empty alias".to_string()
+            })
+        );
+        let spec = CommandSpec::from_json(r#"{"name": "cli", "commands": [{"name": ""}]}"#);
+        assert_eq!(
+            spec,
+            Err(SpecError::InvalidField {
+                field: "commands[0].name".to_string(),
+                expected: "a non-empty command name".to_string()
+            })
+        );
+        let spec = CommandSpec::from_json(
+            r#"{"name": "cli", "commands": [{"name": "a", "aliases": ["x", ""]}]}"#,
+        );
+        assert_eq!(
+            spec,
+            Err(SpecError::InvalidField {
+                field: "commands[0].aliases[1]".to_string(),
+                expected: "a non-empty alias".to_string()
+            })
+        );
+    }
+}
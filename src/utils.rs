@@ -1,3 +1,5 @@
+use crate::{Flag, FlagType};
+
 /// Split arg with "=" to unify arg notations.
 /// --flag=value => ["--flag", "value"]
 /// --flag value => ["--flag", "value"]
@@ -24,3 +26,124 @@ pub fn normalized_args(raw_args: Vec<String>) -> Vec<String> {
         acc
     })
 }
+
+/// Get the index of the first argument that is neither one of the `globals` flags nor its value,
+/// which is the sub command name if the command has one
+pub(crate) fn command_index(args: &[String], globals: &[&Flag]) -> usize {
+    let mut index = 0;
+    while let Some(arg) = args.get(index) {
+        match globals
+            .iter()
+            .find(|f| f.option_index(std::slice::from_ref(arg)).is_some())
+        {
+            Some(flag) if flag.flag_type == FlagType::Bool => index += 1,
+            Some(_) => index += 2,
+            None => break,
+        }
+    }
+    index
+}
+
+/// Get the number of terminal columns taken by the string.
+/// East asian wide characters take two columns and combining characters take none.
+pub fn display_width(s: &str) -> usize {
+    s.chars().map(char_width).sum()
+}
+
+fn char_width(c: char) -> usize {
+    match c as u32 {
+        0x0300..=0x036F
+        | 0x1AB0..=0x1AFF
+        | 0x1DC0..=0x1DFF
+        | 0x200B..=0x200F
+        | 0x20D0..=0x20FF
+        | 0xFE00..=0xFE0F
+        | 0xFE20..=0xFE2F => 0,
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F900..=0x1F9FF
+        | 0x20000..=0x3FFFD => 2,
+        _ if c.is_control() => 0,
+        _ => 1,
+    }
+}
+
+/// Wrap text into lines fitting in `width` columns.
+/// Lines are broken at whitespaces, and words wider than `width` are broken at characters.
+pub fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        let mut line_width = 0;
+
+        for word in paragraph.split_whitespace() {
+            let word_width = display_width(word);
+
+            if line_width > 0 && line_width + 1 + word_width > width {
+                lines.push(std::mem::take(&mut line));
+                line_width = 0;
+            }
+
+            if line_width > 0 {
+                line.push(' ');
+                line_width += 1;
+            }
+
+            if word_width <= width {
+                line.push_str(word);
+                line_width += word_width;
+                continue;
+            }
+
+            for c in word.chars() {
+                let c_width = char_width(c);
+                if line_width > 0 && line_width + c_width > width {
+                    lines.push(std::mem::take(&mut line));
+                    line_width = 0;
+                }
+                line.push(c);
+                line_width += c_width;
+            }
+        }
+
+        lines.push(line);
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{display_width, wrap_text};
+
+    #[test]
+    fn display_width_test() {
+        assert_eq!(display_width("seahorse"), 8);
+        assert_eq!(display_width("タツノオトシゴ"), 14);
+        assert_eq!(display_width("cafe\u{301}"), 4);
+    }
+
+    #[test]
+    fn wrap_text_test() {
+        assert_eq!(
+            wrap_text("Set header of the request", 10),
+            vec!["Set header", "of the", "request"]
+        );
+        assert_eq!(
+            wrap_text("タツノオトシゴ", 6),
+            vec!["タツノ", "オトシ", "ゴ"]
+        );
+        assert_eq!(wrap_text("first\nsecond", 80), vec!["first", "second"]);
+    }
+}